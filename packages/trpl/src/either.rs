@@ -0,0 +1,818 @@
+//! The [`Either`] type returned by [`select`](fn@crate::select) and friends.
+
+use std::{error::Error, fmt, future::Future};
+
+/// A value which is one of two possible types: either `Left` or `Right`.
+///
+/// This is what you get back from [`select`](fn@crate::select) (and its older
+/// name, [`race`](crate::race)): `Left` if the first future finished first,
+/// `Right` if the second one did.
+///
+/// It is a lot like [`Result`], which is *also* a value that is one of two
+/// possible types. The difference is that `Result` tells you which of its two
+/// sides is the “good” one—`Ok`—and which is the “bad” one—`Err`—while
+/// `Either` does not pick a favorite. Because the two are so similar, many of
+/// the methods here are named to match their `Result` counterparts, so that
+/// what you learned about `Result` carries over.
+///
+/// When both sides can be compared, `Either` values can be too, so you can sort
+/// them: every `Left` comes before every `Right`, and two values on the same
+/// side are ordered by the values inside them. And when both sides can be
+/// hashed, so can an `Either`, which means you can use one as a key in a
+/// [`HashMap`](std::collections::HashMap). A `Left` and a `Right` are always
+/// different keys, even if the values inside them are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<A, B> {
+    /// The first of the two possible values.
+    Left(A),
+    /// The second of the two possible values.
+    Right(B),
+}
+
+impl<A, B> Either<A, B> {
+    /// Returns `true` if the value is a `Left`.
+    pub fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    /// Returns `true` if the value is a `Right`.
+    pub fn is_right(&self) -> bool {
+        matches!(self, Either::Right(_))
+    }
+
+    /// Returns `true` if the value is a `Left` containing a value equal to `x`.
+    ///
+    /// This saves a `match` when you want to check both *which* side won a
+    /// race and *what* it produced, for example in an `assert!`.
+    pub fn contains_left(&self, x: &A) -> bool
+    where
+        A: PartialEq,
+    {
+        matches!(self, Either::Left(a) if a == x)
+    }
+
+    /// Returns `true` if the value is a `Right` containing a value equal to
+    /// `x`.
+    pub fn contains_right(&self, x: &B) -> bool
+    where
+        B: PartialEq,
+    {
+        matches!(self, Either::Right(b) if b == x)
+    }
+
+    /// Borrow the value inside the `Either`, without taking ownership of it.
+    ///
+    /// This is the `Either` equivalent of [`Result::as_ref`]: it turns an
+    /// `&Either<A, B>` into an `Either<&A, &B>`, so you can use the methods
+    /// which take `self` without giving up the original.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let winner: Either<String, u32> = Either::Left(String::from("Hello"));
+    ///
+    /// let length = winner.as_ref().map_left(|s| s.len());
+    /// assert_eq!(length, Either::Left(5));
+    ///
+    /// // We only borrowed `winner`, so we can still use it.
+    /// assert_eq!(winner, Either::Left(String::from("Hello")));
+    /// ```
+    pub fn as_ref(&self) -> Either<&A, &B> {
+        match self {
+            Either::Left(a) => Either::Left(a),
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Mutably borrow the value inside the `Either`, so you can change it in
+    /// place.
+    ///
+    /// This is the `Either` equivalent of [`Result::as_mut`]: it turns an
+    /// `&mut Either<A, B>` into an `Either<&mut A, &mut B>`, and whichever side
+    /// it is, changes made through the reference show up in the original.
+    pub fn as_mut(&mut self) -> Either<&mut A, &mut B> {
+        match self {
+            Either::Left(a) => Either::Left(a),
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Transform the value if it is `Left`, leaving a `Right` value untouched.
+    ///
+    /// This is the `Either` equivalent of [`Result::map`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use trpl::Either;
+    ///
+    /// let winner = trpl::block_on(async {
+    ///     let fast = async { "fast" };
+    ///     let slow = async {
+    ///         trpl::sleep(Duration::from_millis(100)).await;
+    ///         42
+    ///     };
+    ///
+    ///     trpl::race(fast, slow).await.map_left(|s| s.len())
+    /// });
+    ///
+    /// assert_eq!(winner, Either::Left(4));
+    /// ```
+    pub fn map_left<F, C>(self, f: F) -> Either<C, B>
+    where
+        F: FnOnce(A) -> C,
+    {
+        match self {
+            Either::Left(a) => Either::Left(f(a)),
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Transform the value if it is `Right`, leaving a `Left` value untouched.
+    ///
+    /// This is the `Either` equivalent of [`Result::map_err`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use trpl::Either;
+    ///
+    /// let winner = trpl::block_on(async {
+    ///     let slow = async {
+    ///         trpl::sleep(Duration::from_millis(100)).await;
+    ///         "slow"
+    ///     };
+    ///     let fast = async { 21 };
+    ///
+    ///     trpl::race(slow, fast).await.map_right(|n| n * 2)
+    /// });
+    ///
+    /// assert_eq!(winner, Either::Right(42));
+    /// ```
+    pub fn map_right<G, D>(self, g: G) -> Either<A, D>
+    where
+        G: FnOnce(B) -> D,
+    {
+        match self {
+            Either::Left(a) => Either::Left(a),
+            Either::Right(b) => Either::Right(g(b)),
+        }
+    }
+
+    /// Transform the value with `on_left` if it is `Left` or `on_right` if it
+    /// is `Right`, keeping it on the same side.
+    ///
+    /// This is like doing [`map_left`](Either::map_left) and
+    /// [`map_right`](Either::map_right) in one step. Unlike
+    /// [`either`](Either::either), the result is still an `Either`.
+    pub fn map_both<C, D, FL, FR>(
+        self,
+        on_left: FL,
+        on_right: FR,
+    ) -> Either<C, D>
+    where
+        FL: FnOnce(A) -> C,
+        FR: FnOnce(B) -> D,
+    {
+        match self {
+            Either::Left(a) => Either::Left(on_left(a)),
+            Either::Right(b) => Either::Right(on_right(b)),
+        }
+    }
+
+    /// Call `f` with the value if it is a `Left`, and use whatever `Either` it
+    /// returns; leave a `Right` alone.
+    ///
+    /// This is [`Result::and_then`] for an `Either` where `Left` means things
+    /// are going well: each step can either keep going with a new `Left` value
+    /// or bail out with a `Right`, and once the value is a `Right`, none of the
+    /// later steps run.
+    pub fn and_then_left<C, F>(self, f: F) -> Either<C, B>
+    where
+        F: FnOnce(A) -> Either<C, B>,
+    {
+        match self {
+            Either::Left(a) => f(a),
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Get `other` if this is a `Left`, or keep this `Right`.
+    ///
+    /// This is [`Result::and`], treating `Left` as success, the same way
+    /// [`and_then_left`](Either::and_then_left) does: as long as things are
+    /// going well, move on to `other`, but once something has gone wrong, keep
+    /// that `Right` instead.
+    pub fn and<C>(self, other: Either<C, B>) -> Either<C, B> {
+        match self {
+            Either::Left(_) => other,
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Keep this `Left`, or get `other` if this is a `Right`.
+    ///
+    /// This is [`Result::or`], treating `Left` as success: if this one did not
+    /// work out, fall back to `other`.
+    pub fn or<D>(self, other: Either<A, D>) -> Either<A, D> {
+        match self {
+            Either::Left(a) => Either::Left(a),
+            Either::Right(_) => other,
+        }
+    }
+
+    /// Turn the `Either` into a single value of type `T`, by calling `on_left`
+    /// if it is `Left` or `on_right` if it is `Right`.
+    ///
+    /// This is the `Either` equivalent of [`Result::map_or_else`], and is often
+    /// the simplest way to handle the result of a [`race`](crate::race) when
+    /// both sides should end up as the same kind of value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let describe = |winner: Either<String, u32>| {
+    ///     winner.either(
+    ///         |name| format!("{name} won"),
+    ///         |n| format!("number {n} won"),
+    ///     )
+    /// };
+    ///
+    /// let ferris = Either::Left(String::from("Ferris"));
+    /// assert_eq!(describe(ferris), "Ferris won");
+    /// assert_eq!(describe(Either::Right(7)), "number 7 won");
+    /// ```
+    pub fn either<T, FL, FR>(self, on_left: FL, on_right: FR) -> T
+    where
+        FL: FnOnce(A) -> T,
+        FR: FnOnce(B) -> T,
+    {
+        match self {
+            Either::Left(a) => on_left(a),
+            Either::Right(b) => on_right(b),
+        }
+    }
+
+    /// Like [`either`](Either::either), but for follow-up work which is itself
+    /// async: call `on_left` or `on_right` to get a future, and await it.
+    ///
+    /// Only the future for the side the value is on is ever created, so only
+    /// that follow-up work runs. This lets you pick up right where a
+    /// [`race`](crate::race) left off, doing something different depending on
+    /// which future won.
+    pub async fn apply<T, FL, FutL, FR, FutR>(
+        self,
+        on_left: FL,
+        on_right: FR,
+    ) -> T
+    where
+        FL: FnOnce(A) -> FutL,
+        FutL: Future<Output = T>,
+        FR: FnOnce(B) -> FutR,
+        FutR: Future<Output = T>,
+    {
+        match self {
+            Either::Left(a) => on_left(a).await,
+            Either::Right(b) => on_right(b).await,
+        }
+    }
+
+    /// Like [`map_left`](Either::map_left), but for an async transformation:
+    /// if the value is a `Left`, call `f` with it and await the future it
+    /// returns.
+    ///
+    /// A `Right` value passes straight through, without calling `f` or
+    /// awaiting anything.
+    pub async fn map_left_async<C, F, Fut>(self, f: F) -> Either<C, B>
+    where
+        F: FnOnce(A) -> Fut,
+        Fut: Future<Output = C>,
+    {
+        match self {
+            Either::Left(a) => Either::Left(f(a).await),
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+
+    /// Like [`map_right`](Either::map_right), but for an async transformation:
+    /// if the value is a `Right`, call `f` with it and await the future it
+    /// returns.
+    ///
+    /// A `Left` value passes straight through, without calling `f` or awaiting
+    /// anything.
+    pub async fn map_right_async<D, F, Fut>(self, f: F) -> Either<A, D>
+    where
+        F: FnOnce(B) -> Fut,
+        Fut: Future<Output = D>,
+    {
+        match self {
+            Either::Left(a) => Either::Left(a),
+            Either::Right(b) => Either::Right(f(b).await),
+        }
+    }
+
+    /// Swap the two sides, turning a `Left` into a `Right` and vice versa.
+    pub fn flip(self) -> Either<B, A> {
+        match self {
+            Either::Left(a) => Either::Right(a),
+            Either::Right(b) => Either::Left(b),
+        }
+    }
+
+    /// Convert a [`Result`] into an `Either`, with `Ok` becoming `Left` and
+    /// `Err` becoming `Right`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let ok: Result<u8, &str> = Ok(1);
+    /// assert_eq!(Either::from_result(ok), Either::Left(1));
+    ///
+    /// let err: Result<u8, &str> = Err("oh no");
+    /// assert_eq!(Either::from_result(err), Either::Right("oh no"));
+    /// ```
+    pub fn from_result(result: Result<A, B>) -> Either<A, B> {
+        match result {
+            Ok(a) => Either::Left(a),
+            Err(b) => Either::Right(b),
+        }
+    }
+
+    /// Convert this `Either` into a [`Result`], with `Left` becoming `Ok` and
+    /// `Right` becoming `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let left: Either<u8, &str> = Either::Left(1);
+    /// assert_eq!(left.into_result(), Ok(1));
+    ///
+    /// let right: Either<u8, &str> = Either::Right("oh no");
+    /// assert_eq!(right.into_result(), Err("oh no"));
+    /// ```
+    pub fn into_result(self) -> Result<A, B> {
+        match self {
+            Either::Left(a) => Ok(a),
+            Either::Right(b) => Err(b),
+        }
+    }
+
+    /// Treat `Left` as success and `Right` as failure, producing a [`Result`]
+    /// which you can use with `?`.
+    ///
+    /// This does exactly the same thing as
+    /// [`into_result`](Either::into_result); the difference is that the name
+    /// says which side you are treating as the error, which makes code using
+    /// it easier to follow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use trpl::Either;
+    ///
+    /// async fn fetch_or_give_up() -> Result<&'static str, Duration> {
+    ///     let max = Duration::from_secs(1);
+    ///     let fetch = async { "data" };
+    ///     let give_up = async {
+    ///         trpl::sleep(max).await;
+    ///         max
+    ///     };
+    ///
+    ///     let data = trpl::race(fetch, give_up).await.ok_or_right()?;
+    ///     Ok(data)
+    /// }
+    ///
+    /// assert_eq!(trpl::block_on(fetch_or_give_up()), Ok("data"));
+    /// ```
+    pub fn ok_or_right(self) -> Result<A, B> {
+        self.into_result()
+    }
+
+    /// Get the value out of a `Left` as `Some`, or `None` if it is a `Right`.
+    ///
+    /// This is the `Either` equivalent of [`Result::ok`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let left: Either<u8, &str> = Either::Left(1);
+    /// assert_eq!(left.left(), Some(1));
+    ///
+    /// let right: Either<u8, &str> = Either::Right("hello");
+    /// assert_eq!(right.left(), None);
+    /// ```
+    pub fn left(self) -> Option<A> {
+        match self {
+            Either::Left(a) => Some(a),
+            Either::Right(_) => None,
+        }
+    }
+
+    /// Get the value out of a `Right` as `Some`, or `None` if it is a `Left`.
+    ///
+    /// This is the `Either` equivalent of [`Result::err`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trpl::Either;
+    ///
+    /// let left: Either<u8, &str> = Either::Left(1);
+    /// assert_eq!(left.right(), None);
+    ///
+    /// let right: Either<u8, &str> = Either::Right("hello");
+    /// assert_eq!(right.right(), Some("hello"));
+    /// ```
+    pub fn right(self) -> Option<B> {
+        match self {
+            Either::Left(_) => None,
+            Either::Right(b) => Some(b),
+        }
+    }
+
+    /// Iterate over a reference to the value if it is a `Left`.
+    ///
+    /// The iterator produces one item for a `Left` and none for a `Right`.
+    /// That makes it easy to pull just the `Left` values out of a whole
+    /// collection of `Either`s, using [`Iterator::flat_map`].
+    pub fn iter_left(&self) -> std::option::IntoIter<&A> {
+        self.as_ref().left().into_iter()
+    }
+
+    /// Iterate over a reference to the value if it is a `Right`.
+    ///
+    /// The iterator produces one item for a `Right` and none for a `Left`.
+    pub fn iter_right(&self) -> std::option::IntoIter<&B> {
+        self.as_ref().right().into_iter()
+    }
+
+    /// Get the value out of a `Left`, or `default` if it is a `Right`.
+    ///
+    /// This is [`Result::unwrap_or`], treating `Left` as success. The `default`
+    /// is built even if it is not needed; if that is expensive, use
+    /// [`left_or_else`](Either::left_or_else) instead.
+    pub fn left_or(self, default: A) -> A {
+        match self {
+            Either::Left(a) => a,
+            Either::Right(_) => default,
+        }
+    }
+
+    /// Get the value out of a `Left`, or the [`Default`] value for `A` if it is
+    /// a `Right`.
+    ///
+    /// This is [`Result::unwrap_or_default`], treating `Left` as success.
+    pub fn left_or_default(self) -> A
+    where
+        A: Default,
+    {
+        match self {
+            Either::Left(a) => a,
+            Either::Right(_) => A::default(),
+        }
+    }
+
+    /// Get the value out of a `Left`, or compute a value of the same type from
+    /// a `Right` by calling `f`.
+    ///
+    /// This is [`Result::unwrap_or_else`], treating `Left` as success. It never
+    /// panics: whichever side the value is on, you get an `A` back.
+    pub fn left_or_else<F: FnOnce(B) -> A>(self, f: F) -> A {
+        match self {
+            Either::Left(a) => a,
+            Either::Right(b) => f(b),
+        }
+    }
+
+    /// Get the value out of a `Left`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is a `Right`. Unlike [`Result::unwrap`], the panic
+    /// message does not include the other value, so this works whether or not
+    /// `B` implements [`Debug`].
+    pub fn unwrap_left(self) -> A {
+        match self {
+            Either::Left(a) => a,
+            Either::Right(_) => {
+                panic!("called `Either::unwrap_left` on a `Right` value")
+            }
+        }
+    }
+
+    /// Get the value out of a `Right`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is a `Left`. Unlike [`Result::unwrap_err`], the
+    /// panic message does not include the other value, so this works whether
+    /// or not `A` implements [`Debug`].
+    pub fn unwrap_right(self) -> B {
+        match self {
+            Either::Left(_) => {
+                panic!("called `Either::unwrap_right` on a `Left` value")
+            }
+            Either::Right(b) => b,
+        }
+    }
+
+    /// Get the value out of a `Left`, panicking with `msg` if it is a `Right`.
+    ///
+    /// Use this instead of [`unwrap_left`](Either::unwrap_left) to explain
+    /// *why* you expected a `Left`, the same way you would use
+    /// [`Option::expect`].
+    ///
+    /// # Panics
+    ///
+    /// Panics with `msg` if the value is a `Right`. As with `unwrap_left`, the
+    /// panic message does not include the other value.
+    pub fn expect_left(self, msg: &str) -> A {
+        match self {
+            Either::Left(a) => a,
+            Either::Right(_) => panic!("{msg}"),
+        }
+    }
+
+    /// Get the value out of a `Right`, panicking with `msg` if it is a `Left`.
+    ///
+    /// Use this instead of [`unwrap_right`](Either::unwrap_right) to explain
+    /// *why* you expected a `Right`, the same way you would use
+    /// [`Option::expect`].
+    ///
+    /// # Panics
+    ///
+    /// Panics with `msg` if the value is a `Left`. As with `unwrap_right`, the
+    /// panic message does not include the other value.
+    pub fn expect_right(self, msg: &str) -> B {
+        match self {
+            Either::Left(_) => panic!("{msg}"),
+            Either::Right(b) => b,
+        }
+    }
+}
+
+impl<T> Either<T, T> {
+    /// Get the value out, whichever side it is on.
+    ///
+    /// This only works when both sides are the same type, which is common when
+    /// you [`race`](crate::race) two futures which produce the same kind of
+    /// answer and do not care which one won.
+    pub fn into_inner(self) -> T {
+        match self {
+            Either::Left(t) | Either::Right(t) => t,
+        }
+    }
+
+    /// Transform the value with `f`, whichever side it is on, keeping it on the
+    /// same side.
+    ///
+    /// When both sides are the same type, this saves writing the same closure
+    /// twice for [`map_both`](Either::map_both).
+    pub fn map_inner<U>(self, f: impl FnOnce(T) -> U) -> Either<U, U> {
+        match self {
+            Either::Left(t) => Either::Left(f(t)),
+            Either::Right(t) => Either::Right(f(t)),
+        }
+    }
+
+    /// Iterate over a reference to the value, whichever side it is on. The
+    /// iterator always produces exactly one item.
+    pub fn iter(&self) -> std::option::IntoIter<&T> {
+        Some(self.as_ref().into_inner()).into_iter()
+    }
+
+    /// Get the value out, whichever side it is on, along with whether it was
+    /// on the right.
+    ///
+    /// The `bool` is `false` for a `Left` and `true` for a `Right`. When you
+    /// race a preferred future (on the left) against a fallback (on the right),
+    /// this gives you the answer plus a flag for “did we end up using the
+    /// fallback?”
+    pub fn normalize(self) -> (T, bool) {
+        match self {
+            Either::Left(t) => (t, false),
+            Either::Right(t) => (t, true),
+        }
+    }
+}
+
+impl<A, B> Either<Either<A, B>, B> {
+    /// Remove one level of nesting from an `Either` whose `Left` side is itself
+    /// an `Either`.
+    ///
+    /// You can end up with one of these by racing an `Either`-producing future
+    /// against another future. A `Left(Left(a))` becomes `Left(a)`, and both
+    /// `Left(Right(b))` and `Right(b)` become `Right(b)`.
+    pub fn flatten(self) -> Either<A, B> {
+        match self {
+            Either::Left(inner) => inner,
+            Either::Right(b) => Either::Right(b),
+        }
+    }
+}
+
+impl<A, B> Either<Option<A>, B> {
+    /// Turn an `Either` with an `Option` on the left into an `Option` of an
+    /// `Either`.
+    ///
+    /// A `Left(None)` becomes `None`, a `Left(Some(a))` becomes
+    /// `Some(Left(a))`, and a `Right(b)` becomes `Some(Right(b))`. This is the
+    /// same idea as [`Result::transpose`], which does this for a `Result` with
+    /// an `Option` inside its `Ok`.
+    pub fn transpose(self) -> Option<Either<A, B>> {
+        match self {
+            Either::Left(Some(a)) => Some(Either::Left(a)),
+            Either::Left(None) => None,
+            Either::Right(b) => Some(Either::Right(b)),
+        }
+    }
+}
+
+impl<A, B, C> Either<(C, A), (C, B)> {
+    /// Pull out the first element of the pair inside, which is the same type
+    /// on both sides.
+    ///
+    /// A `Left((c, a))` becomes `(c, Left(a))`, and a `Right((c, b))` becomes
+    /// `(c, Right(b))`, so the side is kept with the rest of the value.
+    pub fn factor_first(self) -> (C, Either<A, B>) {
+        match self {
+            Either::Left((c, a)) => (c, Either::Left(a)),
+            Either::Right((c, b)) => (c, Either::Right(b)),
+        }
+    }
+}
+
+impl<A, B, C> Either<(A, C), (B, C)> {
+    /// Pull out the second element of the pair inside, which is the same type
+    /// on both sides.
+    ///
+    /// A `Left((a, c))` becomes `(Left(a), c)`, and a `Right((b, c))` becomes
+    /// `(Right(b), c)`, so the side is kept with the rest of the value. For
+    /// example, if you race two futures which each report how long they took,
+    /// this gets you the time no matter which one won.
+    pub fn factor_second(self) -> (Either<A, B>, C) {
+        match self {
+            Either::Left((a, c)) => (Either::Left(a), c),
+            Either::Right((b, c)) => (Either::Right(b), c),
+        }
+    }
+}
+
+impl<A, B> Either<(A, B), (A, B)> {
+    /// Split the pair inside into two `Either`s, each on the same side as the
+    /// original.
+    ///
+    /// A `Left((a, b))` becomes `(Left(a), Left(b))`, and a `Right((a, b))`
+    /// becomes `(Right(a), Right(b))`, so you can work with each half of the
+    /// pair on its own without losing track of which side it came from.
+    pub fn unzip(self) -> (Either<A, A>, Either<B, B>) {
+        match self {
+            Either::Left((a, b)) => (Either::Left(a), Either::Left(b)),
+            Either::Right((a, b)) => (Either::Right(a), Either::Right(b)),
+        }
+    }
+}
+
+impl<A: Clone, B: Clone> Either<&A, &B> {
+    /// Turn an `Either` of references into an `Either` of owned values, by
+    /// cloning whichever value it refers to.
+    ///
+    /// This is the same as [`Option::cloned`]. It undoes
+    /// [`as_ref`](Either::as_ref), for when you borrowed the value to look at
+    /// it and then decide you want your own copy.
+    pub fn cloned(self) -> Either<A, B> {
+        match self {
+            Either::Left(a) => Either::Left(a.clone()),
+            Either::Right(b) => Either::Right(b.clone()),
+        }
+    }
+}
+
+/// When both sides are the same type, you can iterate over an `Either`, which
+/// produces exactly one item: the value inside it, whichever side it is on.
+impl<T> IntoIterator for Either<T, T> {
+    type Item = T;
+    type IntoIter = std::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Some(self.into_inner()).into_iter()
+    }
+}
+
+/// Converting a [`Result`] into an `Either` is the same as calling
+/// [`Either::from_result`]: `Ok` becomes `Left`, and `Err` becomes `Right`.
+impl<A, B> From<Result<A, B>> for Either<A, B> {
+    fn from(result: Result<A, B>) -> Self {
+        Either::from_result(result)
+    }
+}
+
+/// Converting an `Either` into a [`Result`] is the same as calling
+/// [`Either::into_result`]: `Left` becomes `Ok`, and `Right` becomes `Err`.
+impl<A, B> From<Either<A, B>> for Result<A, B> {
+    fn from(either: Either<A, B>) -> Self {
+        either.into_result()
+    }
+}
+
+/// Converting a [`futures::future::Either`] into a `trpl::Either` keeps the
+/// value on the same side. `trpl::Either` used to *be*
+/// `futures::future::Either`, so this is an easy way to keep code written
+/// against that working, e.g. with the output of `futures::future::select`.
+impl<A, B> From<futures::future::Either<A, B>> for Either<A, B> {
+    fn from(either: futures::future::Either<A, B>) -> Self {
+        match either {
+            futures::future::Either::Left(a) => Either::Left(a),
+            futures::future::Either::Right(b) => Either::Right(b),
+        }
+    }
+}
+
+/// Converting a `trpl::Either` into a [`futures::future::Either`] keeps the
+/// value on the same side, for working with code which expects the `futures`
+/// type, such as its `Future` and `Stream` implementations.
+impl<A, B> From<Either<A, B>> for futures::future::Either<A, B> {
+    fn from(either: Either<A, B>) -> Self {
+        match either {
+            Either::Left(a) => futures::future::Either::Left(a),
+            Either::Right(b) => futures::future::Either::Right(b),
+        }
+    }
+}
+
+/// Displaying an `Either` displays whichever value it contains, with nothing
+/// added to say which side it was on.
+///
+/// # Examples
+///
+/// ```
+/// use trpl::Either;
+///
+/// let left: Either<&str, u32> = Either::Left("Hello");
+/// assert_eq!(left.to_string(), "Hello");
+///
+/// let right: Either<&str, u32> = Either::Right(42);
+/// assert_eq!(right.to_string(), "42");
+/// ```
+impl<A, B> fmt::Display for Either<A, B>
+where
+    A: fmt::Display,
+    B: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Either::Left(a) => a.fmt(f),
+            Either::Right(b) => b.fmt(f),
+        }
+    }
+}
+
+/// An `Either` of two errors is itself an error, so you can use `?` to return
+/// it from a function which returns, say, `Result<T, Box<dyn Error>>`.
+///
+/// Its [`Display`](fmt::Display) output is whatever the error it contains
+/// displays, and its [`source`](Error::source) is that error itself, so you
+/// can get at the inner error (and, through *its* `source`, the rest of the
+/// chain) with `downcast_ref`.
+impl<A, B> Error for Either<A, B>
+where
+    A: Error + 'static,
+    B: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Either::Left(a) => Some(a),
+            Either::Right(b) => Some(b),
+        }
+    }
+}
+
+/// A value which is one of three possible types: `Left`, `Middle`, or `Right`.
+///
+/// This is what you get back from [`race3`](crate::race3): it is just like
+/// [`Either`], but with one more option, because there is one more future.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either3<A, B, C> {
+    /// The first of the three possible values.
+    Left(A),
+    /// The second of the three possible values.
+    Middle(B),
+    /// The third of the three possible values.
+    Right(C),
+}
@@ -15,15 +15,77 @@
 //!    release at some point.
 
 // For direct use within the `trpl` crate, *not* re-exported.
-use std::{future::Future, pin::pin};
+use std::{future::Future, pin::Pin, sync::OnceLock, time::Duration};
 
 use futures::future;
 
+mod either;
+
+pub use either::{Either, Either3};
+
 // Re-exports, to be used like `trpl::join`.
+/// The extension trait which gives every [`Future`] methods like `map` and
+/// `then`, so you can transform its output without writing an `async` block.
+///
+/// One thing to watch out for: [`StreamExt`] *also* has a `map` method, but it
+/// does something different. `FutureExt::map` transforms the *one* output of a
+/// future, while `StreamExt::map` transforms *each* item of a stream.
+///
+/// ```
+/// use trpl::FutureExt;
+///
+/// let answer = trpl::block_on(async {
+///     async { 21 }.map(|n| n * 2).await
+/// });
+///
+/// assert_eq!(answer, 42);
+/// ```
+///
+/// ## Fusing futures
+///
+/// Once a future has finished, polling it again is a bug: an `async` block will
+/// panic if you try. That gets in the way when you want to
+/// [`select`](fn@select) between the *same* futures over and over in a loop,
+/// since one of them will finish before the other. `fuse` solves this: a
+/// [`Fuse`]d future just keeps saying “not ready yet” after it has finished,
+/// and you can ask whether it has finished with
+/// [`FusedFuture::is_terminated`].
+///
+/// ```
+/// use std::{pin::pin, time::Duration};
+///
+/// use trpl::{Either, FusedFuture, FutureExt};
+///
+/// let results = trpl::block_on(async {
+///     let mut fast = pin!(async { "fast" }.fuse());
+///     let mut slow = pin!(
+///         async {
+///             trpl::sleep(Duration::from_millis(10)).await;
+///             "slow"
+///         }
+///         .fuse()
+///     );
+///
+///     let mut results = vec![];
+///     while !fast.is_terminated() || !slow.is_terminated() {
+///         match trpl::select(fast.as_mut(), slow.as_mut()).await {
+///             Either::Left(result) | Either::Right(result) => {
+///                 results.push(result)
+///             }
+///         }
+///     }
+///     results
+/// });
+///
+/// assert_eq!(results, vec!["fast", "slow"]);
+/// ```
+pub use futures::future::FutureExt;
+pub use futures::future::{Fuse, FusedFuture};
 pub use futures::{
-    future::{Either, join, join_all, join3},
+    future::{join, join_all, join3, join4, join5, try_join},
     join,
 };
+
 pub use tokio::{
     fs::read_to_string,
     runtime::Runtime,
@@ -42,15 +104,441 @@ pub use tokio::{
         UnboundedReceiver as Receiver, UnboundedSender as Sender,
         unbounded_channel as channel,
     },
-    task::{JoinHandle, spawn as spawn_task, yield_now},
+    task::{JoinHandle, spawn as spawn_task},
     time::{interval, sleep},
 };
 
+/// A point in time, as measured by the async runtime’s clock.
+///
+/// This is Tokio’s `Instant`, which is what all the timer functions in this
+/// crate (like [`sleep_until`] and [`timeout_at`]) take. It works just like
+/// [`std::time::Instant`]—you can add a [`Duration`] to it, or subtract two of
+/// them to get a `Duration`—and you can convert between the two with
+/// [`Instant::from_std`] and [`Instant::into_std`] (or `from` and `into`).
+pub use tokio::time::Instant;
+
+/// Create a channel which can hold at most `capacity` messages at a time.
+///
+/// This is Tokio’s `mpsc::channel`; see the comment on [`channel`] for why we
+/// call it `bounded_channel` instead. Once the channel is full, sending waits:
+/// `send` returns a future which does not finish until the receiver has made
+/// room by taking a message out. Reach for this over [`channel`] when the code
+/// sending messages can get ahead of the code receiving them, and you would
+/// rather slow the sender down (“backpressure”) than let messages pile up in
+/// memory without limit.
+pub use tokio::sync::mpsc::channel as bounded_channel;
+pub use tokio::sync::mpsc::{
+    Receiver as BoundedReceiver, Sender as BoundedSender,
+};
+
+/// Create a channel for sending exactly one value from one task to another.
+///
+/// This is Tokio’s `oneshot::channel`. Where [`channel`] carries a whole stream
+/// of messages, a oneshot channel is for a single handoff: the sender’s `send`
+/// takes `self`, so it can only be called once, and it does not need to wait,
+/// because there is always room for that one value. The receiver is itself a
+/// future: await it to get the value, or an error if the sender was dropped
+/// without sending anything.
+pub use tokio::sync::oneshot::channel as oneshot_channel;
+pub use tokio::sync::oneshot::{
+    Receiver as OneshotReceiver, Sender as OneshotSender,
+};
+
+/// Create a channel which delivers every message to every receiver.
+///
+/// This is Tokio’s `broadcast::channel`. Where [`channel`] has many senders
+/// and one receiver, this one is for “fan-out”: each receiver gets its own
+/// copy of every message sent after it subscribed, so the messages must be
+/// [`Clone`]. Call `subscribe` on the sender to get more receivers. The
+/// channel holds at most `capacity` messages; if a receiver falls so far
+/// behind that the oldest messages get dropped to make room, its next `recv`
+/// returns an error saying how many it missed.
+pub use tokio::sync::broadcast::channel as broadcast_channel;
+pub use tokio::sync::broadcast::{
+    Receiver as BroadcastReceiver, Sender as BroadcastSender,
+};
+
+/// Create a channel which holds only the latest value sent on it, for any
+/// number of receivers to watch.
+///
+/// This is Tokio’s `watch::channel`. Unlike [`channel`] and
+/// [`broadcast_channel`], it is not a queue: sending a new value replaces the
+/// old one, so a receiver which was not paying attention simply misses the
+/// values in between, and sees only the most recent one. That is just right
+/// for things like a configuration setting which several tasks need to keep
+/// up with. Receivers can read the current value with `borrow`, or wait for it
+/// to change with `changed`; call `subscribe` on the sender to get more
+/// receivers.
+pub use tokio::sync::watch::channel as watch_channel;
+pub use tokio::sync::watch::{
+    Receiver as WatchReceiver, Sender as WatchSender,
+};
+
+/// A mutual-exclusion lock which is safe to hold across an `.await`.
+///
+/// This is Tokio’s `Mutex`, not [`std::sync::Mutex`]. The key difference is
+/// that taking the lock is *async*: `lock` returns a future, so a task waiting
+/// for the lock lets other tasks keep running instead of blocking its whole
+/// thread. Because of that, you can keep the [`MutexGuard`] around while you
+/// `.await` something else. Doing that with a `std` lock can deadlock, because
+/// the task waiting for the lock may be sitting on the thread which the task
+/// holding the lock needs in order to make progress. If you never hold the lock
+/// across an `.await`, the `std` one works fine, and is a little faster.
+pub use tokio::sync::{Mutex, MutexGuard};
+
+/// A reader-writer lock which is safe to hold across an `.await`.
+///
+/// This is Tokio’s `RwLock`. Where a [`Mutex`] only ever lets one task at a
+/// time get at the data, an `RwLock` lets any number of tasks hold a *read*
+/// lock at once, as long as nobody holds the *write* lock; a task which wants
+/// to write waits until all the readers are done. Prefer it over `Mutex` when
+/// the data is read much more often than it is written, so the readers do not
+/// have to take turns.
+pub use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A counter of “permits”, for limiting how many tasks can do something at
+/// once.
+///
+/// This is Tokio’s `Semaphore`. It starts out with some number of permits, and
+/// a task which wants to do the limited thing first waits to get one. When it
+/// is done, it drops its permit, which lets the next waiting task have it. So
+/// a semaphore with two permits never lets more than two tasks in at a time.
+///
+/// There are two ways to get a permit. `acquire` borrows the semaphore, so the
+/// permit can only live as long as that borrow. `acquire_owned` works on an
+/// `Arc<Semaphore>` and gives you an [`OwnedSemaphorePermit`], which holds its
+/// own reference to the semaphore, so it can be moved into a spawned task.
+pub use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A token for asking one or more tasks to stop, so they can shut down cleanly.
+///
+/// This is `CancellationToken` from the `tokio-util` crate. Give each task a
+/// clone of the token, and have it await `token.cancelled()` (for example, as
+/// one branch of a [`select!`]) to find out when it should stop. Calling
+/// `cancel()` on any clone of the token wakes up all of them. Unlike aborting a
+/// task, this is *cooperative*: each task decides for itself how to wrap up
+/// what it was doing before it stops.
+pub use tokio_util::sync::CancellationToken;
+
+/// Run a blocking, synchronous function on a thread set aside for that purpose,
+/// getting back a [`JoinHandle`] you can await for its result.
+///
+/// Async code shares a small number of threads, so a function which runs for a
+/// long time without awaiting anything—a heavy computation, or a blocking call
+/// like [`std::thread::sleep`]—holds up every other future on that thread.
+/// Handing that kind of work to `spawn_blocking` keeps it out of their way.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let answer = trpl::block_on(async {
+///     let handle = trpl::spawn_blocking(|| {
+///         std::thread::sleep(Duration::from_millis(10));
+///         42
+///     });
+///
+///     handle.await.unwrap()
+/// });
+///
+/// assert_eq!(answer, 42);
+/// ```
+pub use tokio::task::spawn_blocking;
+
+/// Spawn a task which does not need to be [`Send`], on a [`LocalSet`].
+///
+/// [`spawn_task`] may move a task to another thread, so the task has to be
+/// `Send`, and a future which holds onto something like an [`Rc`] across an
+/// `.await` is not. `spawn_local` never moves its tasks: they all run on the
+/// thread which is running the `LocalSet`. That means it only works from code
+/// running inside a `LocalSet`, which you get by handing your async block to
+/// `LocalSet::run_until`:
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use trpl::LocalSet;
+///
+/// let answer = trpl::block_on(async {
+///     LocalSet::new()
+///         .run_until(async {
+///             let shared = Rc::new(21);
+///             let handle = trpl::spawn_local({
+///                 let shared = Rc::clone(&shared);
+///                 async move {
+///                     trpl::sleep(std::time::Duration::from_millis(1)).await;
+///                     *shared * 2
+///                 }
+///             });
+///             handle.await.unwrap()
+///         })
+///         .await
+/// });
+///
+/// assert_eq!(answer, 42);
+/// ```
+///
+/// [`Rc`]: std::rc::Rc
+pub use tokio::task::spawn_local;
+
+/// A set of tasks which all run on the same thread, so they do not need to be
+/// [`Send`]. See [`spawn_local`] for how to use it.
+pub use tokio::task::LocalSet;
+
+/// Pin a value in place, so that it can be polled as a future.
+///
+/// This is the [`pin!`](std::pin::pin) macro from the standard library, here
+/// so you can get it from the same place as everything else. Many of the
+/// tools for working with more than one future at a time—like
+/// [`futures::future::select`], or the `fuse` loop shown on [`FutureExt`]—
+/// need to poll a future, stop, and then poll it again later from the same
+/// place. That is only safe if the future cannot move in between, and pinning
+/// it is how we promise that it will not.
+///
+/// ```
+/// let answer = trpl::block_on(async {
+///     let mut future = trpl::pin!(async { 42 });
+///     // A pinned future can be awaited through a mutable reference, so it
+///     // stays around to be inspected or polled again afterward.
+///     (&mut future).await
+/// });
+///
+/// assert_eq!(answer, 42);
+/// ```
+pub use std::pin::pin;
+
+/// Build a future out of a closure which gets called every time the future is
+/// polled.
+///
+/// This is the standard library’s `poll_fn`, and it is the quickest way to see
+/// what a future really *is*, without writing out a whole `impl Future`. Each
+/// time the runtime polls the future, it calls the closure with the
+/// [`Context`] for that poll. The closure returns [`Poll::Ready`] with the
+/// output when it is done, or [`Poll::Pending`] if it is not. When it returns
+/// `Pending`, it must arrange for the future to be woken up later, using the
+/// [`Waker`] from the `Context`; otherwise, the runtime will never poll it
+/// again!
+///
+/// This future is ready the second time it is polled:
+///
+/// ```
+/// use trpl::{Context, Poll};
+///
+/// let polls = trpl::block_on(async {
+///     let mut polls = 0;
+///     trpl::poll_fn(|cx: &mut Context<'_>| {
+///         polls += 1;
+///         if polls < 2 {
+///             // Ask to be polled again right away.
+///             cx.waker().wake_by_ref();
+///             Poll::Pending
+///         } else {
+///             Poll::Ready(polls)
+///         }
+///     })
+///     .await
+/// });
+///
+/// assert_eq!(polls, 2);
+/// ```
+///
+/// [`Waker`]: std::task::Waker
+pub use std::future::poll_fn;
+pub use std::task::{Context, Poll};
+
+/// Build a future which is ready right away with `value`.
+///
+/// This and [`pending`], which builds a future which is never ready at all,
+/// come from the `futures` crate. Neither one is very useful on its own, but
+/// both are great for trying out things like [`race`] and
+/// [`select`](fn@select): `ready` always finishes on its very first poll, and
+/// `pending` never finishes, so it can never win a race.
+///
+/// ```
+/// use trpl::Either;
+///
+/// let winner = trpl::block_on(async {
+///     trpl::race(trpl::ready(1), trpl::pending::<i32>()).await
+/// });
+/// assert_eq!(winner, Either::Left(1));
+/// ```
+pub use futures::future::ready;
+
+/// Build a future which is never ready. See [`ready`] for an example.
+pub use futures::future::pending;
+pub use futures::future::{Pending, Ready};
+
+/// Wrap a future so that it can be canceled from somewhere else.
+///
+/// This comes from the `futures` crate. It gives you back two things: a new
+/// future, which does whatever the original one does, and an [`AbortHandle`].
+/// Calling `abort()` on the handle—from another task, say—stops the future the
+/// next time it is polled, and it finishes with `Err(Aborted)` instead of its
+/// usual output. If it finishes first, you get `Ok` with the output. This is a
+/// simpler tool than a [`CancellationToken`], because the future does not get
+/// any say in it: it just stops.
+pub use futures::future::abortable;
+pub use futures::future::{AbortHandle, Aborted};
+
+/// Hand control back to the runtime, so it can run other futures or tasks
+/// before coming back to this one.
+///
+/// Async code only gives other code a chance to run when it reaches an
+/// `.await` which is not ready yet. If a future does a lot of work without ever
+/// doing that, it “starves” everything else. Adding `yield_now().await` to a
+/// long-running loop makes it take turns instead. Here, two spawned tasks
+/// share a single worker thread, and each one yields after every step:
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+///
+/// let order = trpl::block_on_with_threads(1, async {
+///     let order = Arc::new(Mutex::new(String::new()));
+///
+///     let handles: Vec<_> = ['a', 'b']
+///         .into_iter()
+///         .map(|name| {
+///             let order = Arc::clone(&order);
+///             trpl::spawn_task(async move {
+///                 for _ in 0..3 {
+///                     order.lock().unwrap().push(name);
+///                     trpl::yield_now().await;
+///                 }
+///             })
+///         })
+///         .collect();
+///
+///     for handle in handles {
+///         handle.await.unwrap();
+///     }
+///
+///     let order = order.lock().unwrap().clone();
+///     order
+/// });
+///
+/// // Each time one task yields, the other gets a turn, so this typically
+/// // prints `ababab` rather than `aaabbb`. (The runtime decides the exact
+/// // order, so do not rely on it.)
+/// println!("{order}");
+/// assert_eq!(order.matches('a').count(), 3);
+/// assert_eq!(order.matches('b').count(), 3);
+/// ```
+pub use tokio::task::yield_now;
+
+/// Wait on several futures at once, running the code for whichever one finishes
+/// first.
+///
+/// This is Tokio’s `select!` macro. Where the [`select`](fn@select) function
+/// only handles two futures and hands you back an [`Either`], the macro takes
+/// any number of branches, each with its own pattern and handler (and,
+/// optionally, an `if` guard which decides whether to check that branch at
+/// all). As with the function, whichever branches do not finish first are
+/// dropped.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let message = trpl::block_on(async {
+///     let (tx, mut rx) = trpl::channel::<&str>();
+///     drop(tx);
+///
+///     trpl::select! {
+///         Some(message) = rx.recv() => message,
+///         _ = trpl::sleep(Duration::from_millis(10)) => "timed out",
+///     }
+/// });
+///
+/// assert_eq!(message, "timed out");
+/// ```
+pub use tokio::select;
+
 pub use tokio_stream::{
-    Stream, StreamExt, iter as stream_from_iter,
+    Stream, iter as stream_from_iter,
     wrappers::{IntervalStream, UnboundedReceiverStream as ReceiverStream},
 };
 
+/// The extension trait which gives every [`Stream`] methods like `next`, `map`,
+/// and `filter`.
+///
+/// This is the `StreamExt` from `tokio_stream`, rather than the one from
+/// `futures`. The two are very similar, but they are not the same trait, and
+/// if you import both you will get errors about ambiguous method names: stick
+/// to this one! We picked it because it has the methods the book leans on for
+/// working with time, like `timeout` and `throttle`, along with `merge`, none
+/// of which the `futures` version has. (If you do need the `futures` one, it
+/// is there as [`stream::StreamExt`].)
+///
+/// ```
+/// use trpl::StreamExt;
+///
+/// let evens = trpl::block_on(async {
+///     let mut stream = trpl::stream_from_iter(1..=6)
+///         .filter(|n| n % 2 == 0)
+///         .map(|n| n * 10);
+///
+///     let mut evens = vec![];
+///     while let Some(n) = stream.next().await {
+///         evens.push(n);
+///     }
+///     evens
+/// });
+///
+/// assert_eq!(evens, vec![20, 40, 60]);
+/// ```
+pub use tokio_stream::StreamExt;
+
+/// Build a stream which produces exactly one item: the output of a future.
+///
+/// This is the stream equivalent of [`std::iter::once`], and is a good way to
+/// turn a single future into a stream, e.g. to combine it with other streams.
+///
+/// ```
+/// use trpl::StreamExt;
+///
+/// let items = trpl::block_on(async {
+///     trpl::stream_once(async { "Hello" }).collect::<Vec<_>>().await
+/// });
+///
+/// assert_eq!(items, vec!["Hello"]);
+/// ```
+pub use futures::stream::once as stream_once;
+
+/// A stream which can look at its next item without taking it out. See
+/// [`peekable`].
+pub use futures::stream::Peekable;
+
+/// Build a stream which produces each item of an iterator, in order.
+///
+/// This is the `futures` crate’s version of [`stream_from_iter`], which comes
+/// from `tokio_stream`. They produce the same items; the difference is that
+/// the stream this makes can be cloned (when the iterator can), which some
+/// helpers like [`cycle`] need.
+///
+/// ```
+/// use trpl::StreamExt;
+///
+/// let words = trpl::block_on(async {
+///     let mut stream = trpl::iter(["one", "two", "three"]);
+///
+///     let mut words = vec![];
+///     while let Some(word) = stream.next().await {
+///         words.push(word);
+///     }
+///     words
+/// });
+///
+/// assert_eq!(words, vec!["one", "two", "three"]);
+/// ```
+pub use futures::stream::iter;
+
+/// The `futures` crate’s `stream` module, with all of its stream constructors
+/// and types, like [`stream::once`] and [`stream::iter`] (which `trpl` also
+/// re-exports as [`stream_once`] and [`iter`]).
+///
+/// It also has the `futures` version of `StreamExt`, as
+/// [`stream::StreamExt`]. Do not import that alongside [`StreamExt`], or the
+/// method names will clash.
+pub use futures::stream;
+
 /// Run a single future to completion on a bespoke Tokio `Runtime`.
 ///
 /// Every time you call this, a new instance of `tokio::runtime::Runtime` will
@@ -67,11 +555,251 @@ pub fn block_on<F: Future>(future: F) -> F::Output {
     rt.block_on(future)
 }
 
-/// This function has been renamed to `block_on`; please see its documentation.
-/// This function remains to maintain compatibility with the online versions
-/// of the book that use the name `run`.
+/// Run a single future to completion, like [`block_on`], on a runtime with
+/// exactly `worker_threads` threads for running tasks.
+///
+/// The runtime `block_on` creates is already multi-threaded: it starts one
+/// worker thread per CPU core, so tasks from [`spawn_task`] can run in
+/// parallel. This lets you pick the number yourself, which is handy for
+/// experimenting. With `1`, every task has to take turns on the same thread,
+/// so they can run concurrently but never in parallel; with more, you can
+/// watch several CPU-heavy tasks make progress at the same time.
+///
+/// # Panics
+///
+/// Panics if `worker_threads` is zero, or if the runtime cannot be created.
+pub fn block_on_with_threads<F: Future>(
+    worker_threads: usize,
+    future: F,
+) -> F::Output {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(worker_threads)
+        .enable_all()
+        .build()
+        .unwrap();
+    rt.block_on(future)
+}
+
+/// Run a single future to completion, like [`block_on`], but return an error
+/// instead of panicking if the runtime cannot be created.
+///
+/// Creating a runtime can fail, for example if the operating system will not
+/// let us start any more threads. That is rare enough that `block_on` just
+/// panics, which keeps the examples in the book simple, and it remains the one
+/// to use while learning. In a larger program where you would rather handle
+/// that failure yourself, use this instead: it hands back the [`io::Error`]
+/// from creating the runtime.
+///
+/// [`io::Error`]: std::io::Error
+pub fn try_block_on<F: Future>(future: F) -> std::io::Result<F::Output> {
+    let rt = Runtime::new()?;
+    Ok(rt.block_on(future))
+}
+
+/// Run a single future to completion, like [`block_on`], and then shut the
+/// runtime down gracefully.
+///
+/// Online versions of the book used this name before we settled on `block_on`,
+/// and it works the same way, with one difference: what happens afterward to
+/// any work you spawned but did not wait for.
+///
+/// - Tasks from [`spawn_task`] which have not finished are canceled either way.
+///
+/// - Work from [`spawn_blocking`] which is still running is given up to one
+///   second to finish. The plain `block_on` waits for it as long as it takes,
+///   which means a blocking function which never returns would keep your
+///   program from ever exiting!
+///
+/// For learning the basics, the minimal `block_on` is all you need.
 pub fn run<F: Future>(future: F) -> F::Output {
-    block_on(future)
+    let rt = Runtime::new().unwrap();
+    let output = rt.block_on(future);
+    rt.shutdown_timeout(Duration::from_secs(1));
+    output
+}
+
+/// Create a new Tokio [`Runtime`], hand it to `f`, and shut it down once `f`
+/// returns.
+///
+/// Where [`block_on`] takes care of the runtime for you, this lets you use the
+/// runtime directly, from ordinary synchronous code: call `rt.spawn(...)` to
+/// start tasks and `rt.block_on(...)` to wait for things, as many times as you
+/// like, all on the same runtime. Once `f` returns, the runtime is dropped,
+/// which cancels any tasks which are still running, just like `block_on`.
+pub fn with_runtime<T>(f: impl FnOnce(&Runtime) -> T) -> T {
+    let rt = Runtime::new().unwrap();
+    f(&rt)
+}
+
+/// Run a single future to completion on a Tokio `Runtime` which is shared by
+/// every call to this function.
+///
+/// The first call creates the `Runtime`, and every later call reuses it. This
+/// differs from [`block_on`], which creates (and then throws away) a new
+/// `Runtime` every time you call it:
+///
+/// - Use `block_on` when you call it once, usually from `main`, which is what
+///   the book does.
+///
+/// - Use `block_on_shared` when you need to call it many times, e.g. in a loop,
+///   and do not want to pay for setting up a new runtime on each pass.
+///
+/// Since the `Runtime` is shared, tasks you spawn in one call and do not wait
+/// for can keep running during later calls, rather than being shut down when
+/// the call returns.
+///
+/// It is safe to call this from multiple threads at the same time: each call
+/// blocks only its own thread, while the futures all run on the same runtime.
+/// As with `block_on`, you cannot call this from *within* async code which is
+/// already running on a runtime; doing so will panic.
+pub fn block_on_shared<F: Future>(future: F) -> F::Output {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| Runtime::new().unwrap())
+        .block_on(future)
+}
+
+/// Run a single future to completion, like [`block_on`], but give up if it
+/// takes longer than `duration`.
+///
+/// This is [`timeout`] and `block_on` rolled together: it returns `Ok` with the
+/// future’s output if it finishes in time, or `Err` with the `duration` if not.
+/// It is handy for making sure a buggy exercise cannot hang forever.
+pub fn block_on_timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, Duration> {
+    block_on(timeout(duration, future))
+}
+
+/// Run a single future to completion, like [`block_on`], but stop early if the
+/// user presses Ctrl-C.
+///
+/// This returns `Some` with the future’s output if it finishes, or `None` if
+/// Ctrl-C came first, in which case the future is dropped without finishing.
+/// That lets a command-line program clean up and exit normally instead of
+/// being killed partway through.
+///
+/// On Unix-like systems, “Ctrl-C” means the `SIGINT` signal, which a terminal
+/// sends when you press it. On Windows, it means the console’s Ctrl-C event.
+///
+/// The Ctrl-C handler is installed once the runtime starts polling: on each
+/// poll, this checks `future` first and only then the signal, so the handler
+/// goes in the first time `future` is polled and turns out not to be ready.
+/// (A future which is ready right away finishes before that, and never
+/// installs it.) From then on, Ctrl-C no longer kills the program right away,
+/// for the rest of the program’s life, even after this returns; that is how
+/// signal handling works in Tokio.
+///
+/// If the handler for Ctrl-C cannot be set up at all, this just waits for the
+/// future to finish, as `block_on` would.
+pub fn block_on_until_ctrl_c<F: Future>(future: F) -> Option<F::Output> {
+    block_on(async {
+        let ctrl_c = async {
+            if tokio::signal::ctrl_c().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+
+        match race(future, ctrl_c).await {
+            Either::Left(output) => Some(output),
+            Either::Right(()) => None,
+        }
+    })
+}
+
+/// Run several futures to completion on a single runtime, like [`block_on`],
+/// and get all their outputs.
+///
+/// The futures run *concurrently*, via [`join_all`], so this takes about as
+/// long as the slowest of them, rather than as long as all of them added up.
+/// The outputs come back in the same order as the futures went in, no matter
+/// which order they finished in.
+pub fn block_on_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    block_on(join_all(futures))
+}
+
+/// Wait for a spawned task to finish and get its output.
+///
+/// Awaiting a [`JoinHandle`] directly gives you a `Result`, because the task
+/// might not have finished normally: it might have panicked, or been aborted.
+/// This handles that for you, to keep simple examples simple:
+///
+/// - If the task panicked, this panics with the same message.
+/// - If the task was aborted, this panics and says so.
+pub async fn join_task<T>(handle: JoinHandle<T>) -> T {
+    match handle.await {
+        Ok(output) => output,
+        Err(error) if error.is_panic() => {
+            std::panic::resume_unwind(error.into_panic())
+        }
+        Err(_) => panic!("the task was aborted before it could finish"),
+    }
+}
+
+/// Spawn a task to run on its own, without keeping a [`JoinHandle`] for it.
+///
+/// This is for tasks you start purely for what they *do*, not for a value they
+/// produce. The task keeps running in the background even though nothing is
+/// waiting for it—dropping a `JoinHandle` does not stop its task—until it
+/// finishes or the runtime shuts down, whichever comes first. (When the
+/// runtime shuts down, e.g. at the end of [`block_on`], any detached tasks
+/// still running are canceled.)
+///
+/// This does the same thing as `let _ = spawn_task(...);`, but says so.
+pub fn spawn_detached<F>(future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    drop(spawn_task(future));
+}
+
+/// Spawn a task, like [`spawn_task`], with a name which shows up if it panics.
+///
+/// When lots of tasks are running, a bare panic message does not tell you
+/// which one went wrong. If this task panics, the panic message is changed to
+/// start with ``task `name` panicked:``, followed by the original message, so
+/// you see something like ``task `downloader` panicked: oh no``.
+///
+/// That is the only place the name appears. Tokio can attach a name to a task
+/// in a way debugging tools can see, but only with its unstable features
+/// turned on (the `tokio_unstable` configuration flag), which this crate does
+/// not require.
+pub fn spawn_task_named<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let name = name.to_owned();
+    spawn_task(async move {
+        match std::panic::AssertUnwindSafe(future).catch_unwind().await {
+            Ok(output) => output,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(|s| &**s))
+                    .unwrap_or("Box<dyn Any>");
+                panic!("task `{name}` panicked: {message}")
+            }
+        }
+    })
+}
+
+/// Run several fallible futures to completion, and get every one of their
+/// results.
+///
+/// Unlike [`try_join`], this does not stop at the first `Err`: every future
+/// runs until it “settles” one way or the other, and you get back a `Result`
+/// for each of them, in the same order as the futures went in. (This is really
+/// just [`join_all`], which already waits for everything; the name and the
+/// `Result` in the signature make it clear that no error cuts things short.)
+pub async fn join_all_settled<F, T, E>(futures: Vec<F>) -> Vec<Result<T, E>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    join_all(futures).await
 }
 
 /// Run two futures, taking whichever finishes first and canceling the other.
@@ -81,6 +809,10 @@ pub fn run<F: Future>(future: F) -> F::Output {
 /// is that you can work with the first result and then later *also* continue
 /// waiting for the second future.
 ///
+/// The race is *biased* toward `f1`: every time it is polled, it checks `f1`
+/// before `f2`, so if both are ready at the same time, `f1` wins. (See
+/// [`race_biased`], which is the same thing under a name which says so.)
+///
 /// We drop the slower future for the sake of simplicity in the examples: no
 /// need to deal with the tuple and intentionally ignore the second future this
 /// way!
@@ -99,14 +831,17 @@ where
     let f1 = pin!(f1);
     let f2 = pin!(f2);
     match future::select(f1, f2).await {
-        Either::Left((a, _f2)) => Either::Left(a),
-        Either::Right((b, _f1)) => Either::Right(b),
+        future::Either::Left((a, _f2)) => Either::Left(a),
+        future::Either::Right((b, _f1)) => Either::Right(b),
     }
 }
 
 /// This function has been renamed to `select`; please see its documentation.
 /// This function remains to maintain compatibility with the online versions
 /// of the book that use the name `race`.
+///
+/// Like `select`, it always checks `f1` first, so `f1` wins if both futures
+/// are ready at the same time.
 pub async fn race<A, B, F1, F2>(f1: F1, f2: F2) -> Either<A, B>
 where
     F1: Future<Output = A>,
@@ -115,6 +850,1152 @@ where
     select(f1, f2).await
 }
 
+/// Run two futures, taking whichever finishes first and canceling the other,
+/// and turn the winner’s output into a `T`.
+///
+/// This is [`select`](fn@select) followed by [`Either::either`], which is the
+/// most common thing to do with the result of a race anyway: `on_left` gets
+/// `f1`’s output if it wins, or `on_right` gets `f2`’s output if it wins.
+pub async fn select_map<A, B, T, F1, F2, FL, FR>(
+    f1: F1,
+    f2: F2,
+    on_left: FL,
+    on_right: FR,
+) -> T
+where
+    F1: Future<Output = A>,
+    F2: Future<Output = B>,
+    FL: FnOnce(A) -> T,
+    FR: FnOnce(B) -> T,
+{
+    select(f1, f2).await.either(on_left, on_right)
+}
+
+/// Run two futures, taking whichever finishes first, and hand back the other
+/// one instead of canceling it.
+///
+/// [`select`](fn@select) throws away the future which did not finish, but the
+/// [`futures::future::select`] it is built on actually gives it back, still
+/// running. This keeps it, so you can await it later and get its output too.
+/// The sides match up with the winner: if `f1` wins, you get `Left` with its
+/// output and `Left(f2)`; if `f2` wins, you get `Right` with its output and
+/// `Right(f1)`.
+///
+/// Because the loser gets moved back out to you, both futures must be
+/// [`Unpin`]. An `async` block is not, so wrap it with [`pin!`] or
+/// [`Box::pin`] first.
+pub async fn race_keep<A, B, F1, F2>(
+    f1: F1,
+    f2: F2,
+) -> (Either<A, B>, Either<F2, F1>)
+where
+    F1: Future<Output = A> + Unpin,
+    F2: Future<Output = B> + Unpin,
+{
+    match future::select(f1, f2).await {
+        future::Either::Left((a, f2)) => (Either::Left(a), Either::Left(f2)),
+        future::Either::Right((b, f1)) => (Either::Right(b), Either::Right(f1)),
+    }
+}
+
+/// Run three futures, taking whichever finishes first and canceling the others.
+///
+/// This works just like [`select`](fn@select), but for three futures instead of
+/// two, so it returns an [`Either3`] instead of an [`Either`]. As with
+/// `select`, the two slower futures are dropped as soon as one of them
+/// finishes.
+pub async fn race3<A, B, C, F1, F2, F3>(
+    f1: F1,
+    f2: F2,
+    f3: F3,
+) -> Either3<A, B, C>
+where
+    F1: Future<Output = A>,
+    F2: Future<Output = B>,
+    F3: Future<Output = C>,
+{
+    match select(f1, select(f2, f3)).await {
+        Either::Left(a) => Either3::Left(a),
+        Either::Right(Either::Left(b)) => Either3::Middle(b),
+        Either::Right(Either::Right(c)) => Either3::Right(c),
+    }
+}
+
+/// Run two futures, taking whichever finishes first and canceling the other,
+/// always preferring the first future if both are ready at the same time.
+///
+/// This does exactly the same thing as [`select`](fn@select) (and [`race`]),
+/// which also check `f1` before `f2` every time they are polled; the name just
+/// makes the bias explicit where it matters. That trades away *fairness*—if
+/// `f1` is nearly always ready, `f2` may never get a chance to win—in exchange
+/// for *determinism*, which is handy in tests and examples where you want the
+/// same result on every run. (The [`select!`] macro, by contrast, deliberately
+/// picks a branch at random when more than one is ready.)
+pub async fn race_biased<A, B, F1, F2>(f1: F1, f2: F2) -> Either<A, B>
+where
+    F1: Future<Output = A>,
+    F2: Future<Output = B>,
+{
+    select(f1, f2).await
+}
+
+/// Run two futures which produce the same type, taking whichever finishes first
+/// and canceling the other, and report which one it was.
+///
+/// The index is `0` if the first future (`f1`) won, or `1` if the second one
+/// (`f2`) did, matching the indices you get from [`race_all`].
+pub async fn race_with_index<T, F1, F2>(f1: F1, f2: F2) -> (usize, T)
+where
+    F1: Future<Output = T>,
+    F2: Future<Output = T>,
+{
+    match select(f1, f2).await {
+        Either::Left(t) => (0, t),
+        Either::Right(t) => (1, t),
+    }
+}
+
+/// Run any number of futures, taking whichever finishes first and canceling the
+/// rest.
+///
+/// Returns the index of the future which finished first, along with its
+/// output, so you can tell which of the futures in the `Vec` it came from. This
+/// is built on [`futures::future::select_all`], which also hands back the
+/// futures which have not finished yet; as with [`select`](fn@select), we just
+/// drop them.
+///
+/// # Panics
+///
+/// Panics if `futures` is empty, since there is no way to pick a winner if
+/// nothing is racing!
+pub async fn race_all<F: Future>(futures: Vec<F>) -> (usize, F::Output) {
+    assert!(
+        !futures.is_empty(),
+        "called `race_all` with an empty `Vec` of futures"
+    );
+
+    let (output, index, _rest) =
+        future::select_all(futures.into_iter().map(Box::pin)).await;
+    (index, output)
+}
+
+/// Run any number of futures, and get the output of whichever finishes first.
+///
+/// This is [`race_all`] with the index thrown away, for when you only care
+/// about the answer, not where it came from. Taking boxed trait objects means
+/// the futures can each come from a different `async` block, so you can build
+/// up the `Vec` in a loop. As soon as one future finishes, the rest are
+/// dropped, so they never run to completion.
+///
+/// # Panics
+///
+/// Panics if `futures` is empty, just like `race_all`.
+pub async fn first_to_complete<T>(
+    futures: Vec<Pin<Box<dyn Future<Output = T>>>>,
+) -> T {
+    race_all(futures).await.1
+}
+
+/// Run any number of futures, taking whichever finishes first and canceling
+/// the rest, and turn the winner’s output into a common type.
+///
+/// This is the escape hatch for racing more than three futures of different
+/// types. Each branch is a future, then `=>`, then a handler which turns that
+/// future’s output into the result. All the handlers must produce the same
+/// type, so there is no need for an [`Either`]-style enum to say who won:
+/// the handler for the winning future runs, and its result is the result of
+/// the whole race. As with [`select!`], the macro awaits the race itself, so
+/// use it inside an `async` block or function.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let winner = trpl::block_on(async {
+///     trpl::race_n! {
+///         trpl::sleep(Duration::from_secs(1)) => |()| String::from("sleep"),
+///         async { 42 } => |n| format!("number {n}"),
+///         async { "hello" } => |s| s.to_uppercase(),
+///         trpl::pending::<bool>() => |b| b.to_string(),
+///     }
+/// });
+///
+/// assert_eq!(winner, "number 42");
+/// ```
+///
+/// Under the hood, this boxes up each future along with its handler and hands
+/// them all to [`race_all`].
+#[macro_export]
+macro_rules! race_n {
+    ($($future:expr => $handler:expr),+ $(,)?) => {
+        $crate::race_all(::std::vec![$(
+            ::std::boxed::Box::pin($crate::FutureExt::map($future, $handler))
+                as ::std::pin::Pin<
+                    ::std::boxed::Box<
+                        dyn ::std::future::Future<Output = _> + '_,
+                    >,
+                >
+        ),+])
+        .await
+        .1
+    };
+}
+
+/// Run two fallible futures, taking whichever one *succeeds* first.
+///
+/// This is like [`select`](fn@select), except that a future which finishes
+/// with an `Err` does not win the race. Instead:
+///
+/// - If the first future to finish returns `Ok`, that is the result, and the
+///   other future is dropped, just like with `select`.
+///
+/// - If the first future to finish returns `Err`, its error is thrown away and
+///   we wait for the other future, and whatever *it* returns is the result.
+///
+/// So the only way to get an `Err` back is for both futures to fail, in which
+/// case you get the error from whichever one failed *last*. If both are ready
+/// at the same time, the first one (`f1`) is checked first. This is the same
+/// behavior as [`futures::future::select_ok`], but for two futures which do not
+/// need to be the same type.
+pub async fn race_ok<T, E, F1, F2>(f1: F1, f2: F2) -> Result<T, E>
+where
+    F1: Future<Output = Result<T, E>>,
+    F2: Future<Output = Result<T, E>>,
+{
+    let f1 = pin!(f1);
+    let f2 = pin!(f2);
+    match future::select(f1, f2).await {
+        future::Either::Left((Ok(t), _f2)) => Ok(t),
+        future::Either::Left((Err(_e), f2)) => f2.await,
+        future::Either::Right((Ok(t), _f1)) => Ok(t),
+        future::Either::Right((Err(_e), f1)) => f1.await,
+    }
+}
+
+/// Run two fallible futures, taking whichever finishes first, successful or
+/// not, and canceling the other.
+///
+/// When both futures produce the same `Result<T, E>`, racing them with
+/// [`select`](fn@select) gives you an `Either` of two `Result`s, which is
+/// usually more than you want. This gives you just the winner’s `Result`,
+/// whether it is `Ok` or `Err`, and drops the loser. (Compare [`race_ok`],
+/// which keeps waiting if the first one to finish fails.)
+pub async fn race_result<T, E, F1, F2>(f1: F1, f2: F2) -> Result<T, E>
+where
+    F1: Future<Output = Result<T, E>>,
+    F2: Future<Output = Result<T, E>>,
+{
+    select(f1, f2).await.into_inner()
+}
+
+/// Get the current time, as an [`Instant`].
+///
+/// This is the same as calling [`Instant::now`], but it gives you one obvious
+/// place to start when you want to work out a deadline for [`sleep_until`] or
+/// [`timeout_at`]: take `now()` and add a [`Duration`] to it.
+pub fn now() -> Instant {
+    Instant::now()
+}
+
+/// Wait until `deadline`.
+///
+/// Where [`sleep`] waits for a *length* of time starting from whenever you call
+/// it, this waits until a specific *point* in time. That makes it handy when
+/// several pieces of code need to wake up at the same moment, or when you want
+/// something to happen on a schedule no matter how long the work in between
+/// takes. If `deadline` has already passed, this finishes right away.
+pub async fn sleep_until(deadline: Instant) {
+    tokio::time::sleep_until(deadline).await
+}
+
+/// Wait for a random length of time, at least `min` and less than `max`.
+///
+/// Adding a bit of randomness (“jitter”) to delays keeps lots of tasks which
+/// all started at the same time from all waking up at the same time too, for
+/// example when they are all [`retry`]ing a request to the same server. If
+/// `min` and `max` are equal, this just sleeps for `min`.
+///
+/// The randomness here is good enough for spreading out delays, but *not* for
+/// anything security-related. It comes from the random keys the standard
+/// library uses for `HashMap`, so this crate does not need another dependency.
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`.
+pub async fn sleep_random(min: Duration, max: Duration) {
+    use std::{
+        hash::{BuildHasher, Hasher, RandomState},
+        time::SystemTime,
+    };
+
+    assert!(
+        min <= max,
+        "called `sleep_random` with `min` greater than `max`"
+    );
+
+    let range = (max - min).as_nanos();
+    let offset = if range == 0 {
+        0
+    } else {
+        let mut hasher = RandomState::new().build_hasher();
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
+        hasher.write_u128(now.unwrap_or_default().as_nanos());
+        u128::from(hasher.finish()) % range
+    };
+
+    // `offset` is at most the `u64` from `finish`, so the cast cannot truncate.
+    sleep(min + Duration::from_nanos(offset as u64)).await
+}
+
+/// Run a future, giving up on it if it does not finish within `duration`.
+///
+/// Returns `Ok` with the future’s output if it finishes in time, or `Err` with
+/// the `duration` if it does not. This is the same shape as the `timeout`
+/// function we build ourselves in the book, just with the arguments in the
+/// same order as Tokio’s own `timeout`.
+pub async fn timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, Duration> {
+    tokio::time::timeout(duration, future)
+        .await
+        .map_err(|_elapsed| duration)
+}
+
+/// Run a future, giving up on it if it does not finish within `duration`, and
+/// report the result as the outcome of a race.
+///
+/// This does the same job as [`timeout`], but instead of a `Result` it returns
+/// an [`Either`], just like [`race`]: `Left` with the future’s output if it
+/// wins, or `Right(())` if the timer wins. That is because it *is* a race,
+/// between the future and a [`sleep`]!
+pub async fn race_timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Either<F::Output, ()> {
+    race(future, sleep(duration)).await
+}
+
+/// Run a future, giving up on it if it has not finished by `deadline`.
+///
+/// This is to [`timeout`] what [`sleep_until`] is to [`sleep`]: instead of a
+/// length of time, it takes a specific point in time. That makes it easy to
+/// give several operations one shared deadline, since each one only gets
+/// whatever time is left. Returns `Ok` with the future’s output if it finishes
+/// in time, or `Err` with the `deadline` if it does not.
+pub async fn timeout_at<F: Future>(
+    deadline: Instant,
+    future: F,
+) -> Result<F::Output, Instant> {
+    tokio::time::timeout_at(deadline, future)
+        .await
+        .map_err(|_elapsed| deadline)
+}
+
+/// Like [`timeout`], but also reports how much of `duration` was left over
+/// when the future finished.
+///
+/// The remaining time is measured with the runtime’s clock, so it is only as
+/// precise as that clock, and it includes the (small!) time spent getting back
+/// to your code after the future finishes. Treat it as an approximation, not a
+/// benchmark.
+pub async fn timeout_with_remaining<F: Future>(
+    duration: Duration,
+    future: F,
+) -> Result<(F::Output, Duration), Duration> {
+    let start = Instant::now();
+    let output = timeout(duration, future).await?;
+    Ok((output, duration.saturating_sub(start.elapsed())))
+}
+
+/// Run a future, falling back to a default value if it does not finish within
+/// `duration`.
+///
+/// This is [`timeout`] for when you do not need to know whether it timed out:
+/// you get the future’s output if it finishes in time, or the output type’s
+/// [`Default`] value (like `0`, an empty `String`, or `None`) if not.
+pub async fn timeout_or_default<F>(duration: Duration, future: F) -> F::Output
+where
+    F: Future,
+    F::Output: Default,
+{
+    timeout(duration, future).await.unwrap_or_default()
+}
+
+/// Run two futures concurrently, like [`join`](fn@join), but give up on both if
+/// they do not *both* finish within `duration`.
+///
+/// The time limit is for the two of them together, not for each one, and it
+/// starts when the first poll happens. So it is about how long the whole
+/// `join` takes, which is as long as the slower of the two. Returns `Ok` with
+/// both outputs if they finish in time, or `Err` with the `duration` if not; in
+/// that case, even the one which finished is thrown away.
+pub async fn join_with_timeout<A, B, F1, F2>(
+    duration: Duration,
+    f1: F1,
+    f2: F2,
+) -> Result<(A, B), Duration>
+where
+    F1: Future<Output = A>,
+    F2: Future<Output = B>,
+{
+    timeout(duration, join(f1, f2)).await
+}
+
+/// Method syntax for [`timeout`], available on every future.
+///
+/// With this trait in scope, `some_future.with_timeout(duration)` does the same
+/// thing as `trpl::timeout(duration, some_future)`, in the same style as the
+/// methods on [`FutureExt`]:
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use trpl::TimeoutExt;
+///
+/// let result = trpl::block_on(async {
+///     async { "Hello" }.with_timeout(Duration::from_secs(1)).await
+/// });
+///
+/// assert_eq!(result, Ok("Hello"));
+/// ```
+pub trait TimeoutExt: Future + Sized {
+    /// Give up on this future if it does not finish within `duration`. See
+    /// [`timeout`] for details.
+    fn with_timeout(
+        self,
+        duration: Duration,
+    ) -> impl Future<Output = Result<Self::Output, Duration>>;
+}
+
+impl<F: Future> TimeoutExt for F {
+    fn with_timeout(
+        self,
+        duration: Duration,
+    ) -> impl Future<Output = Result<Self::Output, Duration>> {
+        timeout(duration, self)
+    }
+}
+
+/// Run a future, and measure how long it took to finish.
+///
+/// Returns the future’s output along with the time from when this started
+/// awaiting it until it finished. That is a handy way to *see* concurrency at
+/// work: two one-second sleeps awaited one after the other take about two
+/// seconds, but [`join`](fn@join)ed together they take about one.
+pub async fn elapsed<F: Future>(future: F) -> (F::Output, Duration) {
+    let start = Instant::now();
+    let output = future.await;
+    (output, start.elapsed())
+}
+
+/// Wait for a spawned task to finish, giving up on it if it does not finish
+/// within `duration`.
+///
+/// This is [`join_task`] with a [`timeout`]: it returns `Ok` with the task’s
+/// output if the task finishes in time, or `Err` with the `duration` if it
+/// does not. On timeout, the task is *aborted*, so it will not keep running in
+/// the background after you have given up on it. (This is different from just
+/// dropping a [`JoinHandle`], which lets the task keep going.)
+///
+/// # Panics
+///
+/// Like `join_task`, this panics if the task panicked or was aborted by
+/// something else before it could finish.
+pub async fn timeout_join<T>(
+    duration: Duration,
+    handle: JoinHandle<T>,
+) -> Result<T, Duration> {
+    let abort_handle = handle.abort_handle();
+    match timeout(duration, join_task(handle)).await {
+        Ok(output) => Ok(output),
+        Err(duration) => {
+            abort_handle.abort();
+            Err(duration)
+        }
+    }
+}
+
+/// Spawn a task, giving up on it if it does not finish within `duration`.
+///
+/// Like [`spawn_task`], this starts running the task right away, so you must
+/// call it from within a runtime, e.g. inside [`block_on`]. The returned future
+/// resolves to `Ok` with the task’s output if the task finishes in time, or to
+/// `Err` with the `duration` if it does not.
+///
+/// On timeout, the task is *aborted*, just as with [`timeout_join`]: it will
+/// not keep running in the background after you have given up on it.
+pub fn spawn_task_with_timeout<F>(
+    duration: Duration,
+    future: F,
+) -> impl Future<Output = Result<F::Output, Duration>>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    timeout_join(duration, spawn_task(future))
+}
+
+/// Try an async operation up to `attempts` times, waiting `delay` after each
+/// failure before trying again.
+///
+/// Returns the first `Ok` result, or the last `Err` if every attempt fails.
+///
+/// Notice that this takes a *closure which makes a future*, rather than a
+/// future. Once a future has finished, it is done for good: awaiting it again
+/// does not run the operation again. So to try again, we need a brand new
+/// future each time, and `make_future` is how we get one.
+///
+/// # Panics
+///
+/// Panics if `attempts` is zero, since then there would be no result at all.
+pub async fn retry<T, E, Fut, F>(
+    attempts: usize,
+    delay: Duration,
+    mut make_future: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    assert!(attempts > 0, "called `retry` with zero attempts");
+
+    let mut remaining = attempts;
+    loop {
+        remaining -= 1;
+        match make_future().await {
+            Ok(t) => return Ok(t),
+            Err(e) if remaining == 0 => return Err(e),
+            Err(_e) => sleep(delay).await,
+        }
+    }
+}
+
+/// Try an async operation up to `attempts` times, giving each attempt at most
+/// `per_attempt` to finish.
+///
+/// This is [`retry`] and [`timeout`] together: an attempt which takes too long
+/// is given up on and counts as a failure, just like one which returns an
+/// `Err`. The next attempt starts right away. If every attempt fails, the
+/// error says how the *last* one failed: `Left` with its error if it returned
+/// an `Err`, or `Right` with `per_attempt` if it timed out.
+///
+/// # Panics
+///
+/// Panics if `attempts` is zero, since then there would be no result at all.
+pub async fn timeout_retry<T, E, Fut, F>(
+    attempts: usize,
+    per_attempt: Duration,
+    mut make_future: F,
+) -> Result<T, Either<E, Duration>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    assert!(attempts > 0, "called `timeout_retry` with zero attempts");
+
+    let mut remaining = attempts;
+    loop {
+        remaining -= 1;
+        let error = match timeout(per_attempt, make_future()).await {
+            Ok(Ok(t)) => return Ok(t),
+            Ok(Err(e)) => Either::Left(e),
+            Err(duration) => Either::Right(duration),
+        };
+        if remaining == 0 {
+            return Err(error);
+        }
+    }
+}
+
+/// Turn the receiving end of a [`channel`] into a [`Stream`] of the messages
+/// sent on it.
+///
+/// This is the same as calling [`ReceiverStream::new`]. The stream ends once
+/// every [`Sender`] for the channel has been dropped and all the messages
+/// already sent have been produced.
+pub fn receiver_stream<T>(rx: Receiver<T>) -> impl Stream<Item = T> {
+    ReceiverStream::new(rx)
+}
+
+/// Create a [`channel`], with the receiving end already turned into a
+/// [`Stream`].
+///
+/// This is [`channel`] and [`receiver_stream`] in one step: send messages with
+/// the [`Sender`] from wherever they are produced, and consume them as a stream
+/// somewhere else. As with `receiver_stream`, the stream ends once every
+/// `Sender` has been dropped and all the messages have been produced.
+pub fn stream_pair<T>() -> (Sender<T>, impl Stream<Item = T>) {
+    let (tx, rx) = channel();
+    (tx, receiver_stream(rx))
+}
+
+/// Build a [`Stream`] which gets each of its items by calling `f` and awaiting
+/// the future it returns.
+///
+/// As long as the future produces `Some(item)`, the stream produces `item`.
+/// Once it produces `None`, the stream ends, and `f` is never called again.
+/// This is a handy way to make a stream out of thin air, without a channel or
+/// an existing collection to start from. It is built on
+/// [`futures::stream::unfold`], which is more flexible, but also a bit harder
+/// to read.
+pub fn stream_from_fn<T, Fut, F>(f: F) -> impl Stream<Item = T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    futures::stream::unfold(f, |mut f| async move {
+        let item = f().await?;
+        Some((item, f))
+    })
+}
+
+/// Build a [`Stream`] which produces a value from calling `f`, over and over,
+/// forever.
+///
+/// Where [`stream_from_fn`] takes a closure which returns a future and can end
+/// the stream, this takes an ordinary closure and never ends, so use [`take`]
+/// to get a limited number of items out of it.
+pub fn repeat_with<T, F: FnMut() -> T>(f: F) -> impl Stream<Item = T> {
+    futures::stream::repeat_with(f)
+}
+
+/// Receive every message sent on a [`channel`], collecting them into a `Vec`.
+///
+/// This keeps waiting for messages until every [`Sender`] for the channel has
+/// been dropped, so make sure that happens, or it will wait forever!
+pub async fn collect_vec<T>(mut rx: Receiver<T>) -> Vec<T> {
+    let mut messages = Vec::new();
+    while let Some(message) = rx.recv().await {
+        messages.push(message);
+    }
+    messages
+}
+
+/// Receive every message sent on a [`channel`] from ordinary synchronous code,
+/// collecting them into a `Vec`.
+///
+/// This is [`collect_vec`], run to completion on `rt` with
+/// [`Runtime::block_on`]. It blocks the current thread until every [`Sender`]
+/// for the channel has been dropped, so the tasks doing the sending need to be
+/// running on `rt`, for example by starting them with `rt.spawn`. Like
+/// `block_on`, you cannot call this from async code.
+pub fn blocking_recv_all<T>(rt: &Runtime, rx: Receiver<T>) -> Vec<T> {
+    rt.block_on(collect_vec(rx))
+}
+
+/// Wait for the next message on a [`channel`], giving up if none arrives within
+/// `duration`.
+///
+/// This is [`timeout`] wrapped around `rx.recv()`, so there are three ways it
+/// can turn out:
+///
+/// - `Ok(Some(message))` if a message arrives in time.
+/// - `Ok(None)` if every [`Sender`] has been dropped, so no message will ever
+///   arrive.
+/// - `Err(duration)` if nothing happens in time.
+///
+/// On timeout, no message is lost: one which arrives later is still waiting
+/// in the channel the next time you receive.
+pub async fn recv_timeout<T>(
+    rx: &mut Receiver<T>,
+    duration: Duration,
+) -> Result<Option<T>, Duration> {
+    timeout(duration, rx.recv()).await
+}
+
+/// Receive every message sent on a [`channel`] within `duration`, collecting
+/// them into a `Vec`.
+///
+/// Where [`timeout`] is all-or-nothing, this gathers as much as it can within
+/// the time budget: once `duration` has passed, it stops waiting and hands
+/// back whatever messages arrived before then, which might be none at all. It
+/// also stops early, like [`collect_vec`], if every [`Sender`] is dropped.
+/// Since it takes ownership of `rx`, any messages sent after the deadline are
+/// dropped along with it.
+pub async fn timeout_collecting<T>(
+    duration: Duration,
+    mut rx: Receiver<T>,
+) -> Vec<T> {
+    let deadline = Instant::now() + duration;
+    let mut messages = Vec::new();
+    while let Ok(Some(message)) = timeout_at(deadline, rx.recv()).await {
+        messages.push(message);
+    }
+    messages
+}
+
+/// Receive messages from two [`channel`]s at once, calling `on_item` with each
+/// one, until both channels are closed.
+///
+/// Each time around the loop, this [`select`](fn@select)s between the next
+/// message on `rx_a` and the next message on `rx_b`, so messages get handled
+/// in the order they arrive, whichever channel they come from. Once one
+/// channel is closed—every [`Sender`] for it has been dropped and its messages
+/// are all handled—it keeps going with just the other one, and it finishes
+/// once that one is closed too. (Canceling a `recv` does not lose a message,
+/// so this never drops one on the floor.)
+pub async fn select_loop<T, F>(
+    mut on_item: F,
+    mut rx_a: Receiver<T>,
+    mut rx_b: Receiver<T>,
+) where
+    F: FnMut(T),
+{
+    loop {
+        match select(rx_a.recv(), rx_b.recv()).await {
+            Either::Left(Some(item)) | Either::Right(Some(item)) => {
+                on_item(item)
+            }
+            Either::Left(None) => {
+                while let Some(item) = rx_b.recv().await {
+                    on_item(item);
+                }
+                return;
+            }
+            Either::Right(None) => {
+                while let Some(item) = rx_a.recv().await {
+                    on_item(item);
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Get every item from a stream, collecting them into a `Vec`.
+///
+/// This is the same as calling `.collect::<Vec<_>>()` from [`StreamExt`], but
+/// without needing to import it. It waits until the stream ends, so only use it
+/// with streams which *do* end!
+pub async fn collect_stream<S: Stream>(stream: S) -> Vec<S::Item> {
+    stream.collect().await
+}
+
+/// Call `f` with each item from a stream, in order.
+///
+/// This drives the stream to completion, finishing only once the stream ends,
+/// so it is the stream version of a `for` loop. (The `futures` crate’s
+/// `StreamExt::for_each` wants a closure which returns a future; this takes an
+/// ordinary closure, which is all you need for things like printing.)
+pub async fn for_each<S, F>(stream: S, mut f: F)
+where
+    S: Stream,
+    F: FnMut(S::Item),
+{
+    futures::StreamExt::for_each(stream, move |item| {
+        f(item);
+        future::ready(())
+    })
+    .await
+}
+
+/// Combine every item of a stream into a single value, starting from `init`.
+///
+/// This is the stream version of [`Iterator::fold`]: `f` gets the value so far
+/// and the next item, and returns the new value so far. Once the stream ends,
+/// you get whatever the last call returned (or `init`, if the stream was
+/// empty). Like [`collect_stream`], it waits for the end of the stream.
+pub async fn fold<S, B, F>(stream: S, init: B, f: F) -> B
+where
+    S: Stream,
+    F: FnMut(B, S::Item) -> B,
+{
+    stream.fold(init, f).await
+}
+
+/// Build a [`Stream`] which produces `()` once every `period`, forever.
+///
+/// This is a more convenient way to write `IntervalStream::new(interval(...))`
+/// when you only care *that* the timer ticked. The first tick happens right
+/// away, and every later tick follows `period` after the one before it.
+///
+/// If the rest of your code is busy for long enough that it misses some ticks,
+/// the stream uses Tokio’s default [`MissedTickBehavior::Burst`]: it produces
+/// all the missed ticks as fast as it can until it has caught up, and then goes
+/// back to ticking once every `period`.
+///
+/// [`MissedTickBehavior::Burst`]: tokio::time::MissedTickBehavior::Burst
+pub fn interval_stream(period: Duration) -> impl Stream<Item = ()> {
+    IntervalStream::new(interval(period)).map(|_instant| ())
+}
+
+/// Build a [`Stream`] which produces `()` once every `period`, forever, with
+/// every tick landing on a round multiple of `period` on the wall clock.
+///
+/// [`interval_stream`] ticks right away, so its ticks fall wherever the
+/// program happened to be when it started. This one lines them up with the
+/// clock instead, the way a scheduler running a job “every minute” runs it at
+/// the top of each minute, not 37 seconds past. To do that, it looks at how
+/// far the current time (as a [`SystemTime`] since the Unix epoch) is past the
+/// last multiple of `period`, and waits out the rest of that `period` before
+/// the first tick. So the first tick is always less than `period` away, and
+/// every tick after it follows `period` after the one before.
+///
+/// Note that the ticks line up with multiples of `period` counted from the
+/// Unix epoch, *not* from when the program started. Counting from program
+/// start would not need any of this: every interval started near the top of
+/// `main` already ticks on those multiples. Lining up with the wall clock is
+/// what makes ticks land on round times like the top of each minute, and what
+/// keeps two separate programs ticking in step. It also means that if the
+/// system clock is changed after this is called, the ticks do not follow it.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+///
+/// [`SystemTime`]: std::time::SystemTime
+pub fn interval_aligned(period: Duration) -> impl Stream<Item = ()> {
+    use std::time::SystemTime;
+
+    assert!(
+        !period.is_zero(),
+        "called `interval_aligned` with a zero period"
+    );
+
+    let since_epoch = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let past_boundary = since_epoch.as_nanos() % period.as_nanos();
+    // This is less than `period`, so the cast only truncates for periods of
+    // more than 500 years, which are not worth worrying about here.
+    let until_boundary = if past_boundary == 0 {
+        Duration::ZERO
+    } else {
+        period - Duration::from_nanos(past_boundary as u64)
+    };
+
+    let start = Instant::now() + until_boundary;
+    IntervalStream::new(tokio::time::interval_at(start, period))
+        .map(|_instant| ())
+}
+
+/// Build a [`Stream`] which ticks `count` times, once every `period`, and then
+/// ends.
+///
+/// Each tick produces its index, counting up from `0` to `count - 1`. Like
+/// [`interval_stream`], the first tick happens right away. Unlike it, this
+/// stream ends, so it is safe to use with things like [`collect_stream`] which
+/// wait for the end of the stream.
+pub fn interval_count(
+    period: Duration,
+    count: usize,
+) -> impl Stream<Item = usize> {
+    enumerate(interval_stream(period))
+        .take(count)
+        .map(|(index, ())| index)
+}
+
+/// Build a [`Stream`] which counts up from `0`, producing the next number once
+/// every `period`, forever.
+///
+/// This is [`interval_stream`], except that each tick tells you which tick it
+/// is: `0`, then `1`, then `2`, and so on. Like `interval_stream`, it never
+/// ends on its own; use [`take`] to stop after a certain number of ticks, or
+/// [`interval_count`] to do both at once.
+pub fn counting_interval(period: Duration) -> impl Stream<Item = u64> {
+    enumerate(interval_stream(period)).map(|(n, ())| n as u64)
+}
+
+/// Combine two streams into one stream which produces the items from both.
+///
+/// Items come out in the order they become ready, no matter which stream they
+/// came from, so a fast stream’s items are not held up waiting on a slow
+/// stream. The merged stream ends once *both* streams have ended.
+pub fn merge<S1, S2, T>(s1: S1, s2: S2) -> impl Stream<Item = T>
+where
+    S1: Stream<Item = T>,
+    S2: Stream<Item = T>,
+{
+    s1.merge(s2)
+}
+
+/// Combine two streams into one stream which takes turns between them.
+///
+/// Where [`merge`] is about *when* items are ready, this is about *fairness*.
+/// Each time you ask the combined stream for an item, it first asks whichever
+/// stream’s turn it is, and the turns alternate, starting with `s1`: `s1`,
+/// then `s2`, then `s1`, and so on. So when both streams always have an item
+/// ready, their items come out strictly alternating. If the stream whose turn
+/// it is has nothing ready yet, this asks the other one instead of waiting;
+/// once one stream ends, the rest of the items come from the other. The
+/// combined stream ends once *both* streams have ended.
+pub fn round_robin<S1, S2, T>(s1: S1, s2: S2) -> impl Stream<Item = T>
+where
+    S1: Stream<Item = T>,
+    S2: Stream<Item = T>,
+{
+    futures::stream::select(s1, s2)
+}
+
+/// Combine two streams into one stream of pairs, taking one item from each.
+///
+/// Each pair waits until both streams have produced their next item. The
+/// zipped stream ends as soon as *either* stream ends, so any extra items in
+/// the longer stream are never produced.
+pub fn zip<S1, S2>(s1: S1, s2: S2) -> impl Stream<Item = (S1::Item, S2::Item)>
+where
+    S1: Stream,
+    S2: Stream,
+{
+    futures::StreamExt::zip(s1, s2)
+}
+
+/// Slow a stream down so that it produces at most one item per `period`.
+///
+/// No items are dropped: if the stream has items ready faster than that, they
+/// are *delayed* until `period` has passed since the previous one. The first
+/// item is not delayed at all.
+pub fn throttle<S: Stream>(
+    stream: S,
+    period: Duration,
+) -> impl Stream<Item = S::Item> {
+    stream.throttle(period)
+}
+
+/// Only produce an item from a stream once the stream has gone quiet for
+/// `quiet`, dropping any items which are replaced before then.
+///
+/// This is different from [`throttle`], which delays items but keeps all of
+/// them. Here, each item starts a timer. If another item arrives before the
+/// timer runs out, the earlier item is dropped, and the timer starts over for
+/// the new one. Once `quiet` passes without anything new arriving, the latest
+/// item is produced. So a burst of items comes out as just the last one, about
+/// `quiet` after the burst ends. This is handy for things like search boxes,
+/// where you only want to react once someone stops typing.
+///
+/// When the original stream ends, the item it was waiting on (if any) comes
+/// out right away, without waiting for the rest of the quiet period, and then
+/// this stream ends too.
+pub fn debounce<S: Stream>(
+    stream: S,
+    quiet: Duration,
+) -> impl Stream<Item = S::Item> {
+    let stream = Box::pin(futures::StreamExt::fuse(stream));
+    futures::stream::unfold(stream, move |mut stream| async move {
+        let mut latest = stream.next().await?;
+        loop {
+            match timeout(quiet, stream.next()).await {
+                Ok(Some(newer)) => latest = newer,
+                Ok(None) | Err(_) => return Some((latest, stream)),
+            }
+        }
+    })
+}
+
+/// Transform each item of a stream with `f`.
+///
+/// This is the stream version of [`Iterator::map`]. It does the same thing as
+/// calling `map` from [`StreamExt`], but without needing to import it.
+pub fn map_stream<S, T, F>(stream: S, f: F) -> impl Stream<Item = T>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> T,
+{
+    stream.map(f)
+}
+
+/// Transform each item of a stream with `f`, keeping only the `Some` results.
+///
+/// This is the stream version of [`Iterator::filter_map`], and it takes the
+/// same kind of *ordinary* closure. That is different from the `filter_map`
+/// method on the `futures` crate’s `StreamExt`, which wants a closure which
+/// returns a *future* of an `Option`; use that one if you need to `.await`
+/// something while deciding what to do with each item.
+pub fn filter_map<S, T, F>(stream: S, f: F) -> impl Stream<Item = T>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Option<T>,
+{
+    stream.filter_map(f)
+}
+
+/// Drop any item of a stream which is equal to the item right before it.
+///
+/// Only *adjacent* duplicates are removed: `1, 1, 2, 1` becomes `1, 2, 1`, not
+/// `1, 2`, because the last `1` comes after a `2`. That is just what you want
+/// for something like a sensor which keeps reporting the same reading, where
+/// you only care when it changes. Each item is cloned so it can be compared
+/// with the next one.
+pub fn dedup<S: Stream>(stream: S) -> impl Stream<Item = S::Item>
+where
+    S::Item: PartialEq + Clone,
+{
+    let mut previous = None;
+    stream.filter_map(move |item| {
+        if previous.as_ref() == Some(&item) {
+            None
+        } else {
+            previous = Some(item.clone());
+            Some(item)
+        }
+    })
+}
+
+/// Transform a stream with `f`, carrying some state along from item to item.
+///
+/// This is the stream version of [`Iterator::scan`]. The state starts out as
+/// `initial`, and `f` gets a mutable reference to it along with each item, so
+/// it can both update the state and decide what to produce. For example, it can
+/// keep a running total. If `f` returns `None`, the stream ends right there,
+/// even if the original stream has more items.
+pub fn scan<S, St, T, F>(
+    stream: S,
+    initial: St,
+    mut f: F,
+) -> impl Stream<Item = T>
+where
+    S: Stream,
+    F: FnMut(&mut St, S::Item) -> Option<T>,
+{
+    futures::StreamExt::scan(stream, initial, move |state, item| {
+        future::ready(f(state, item))
+    })
+}
+
+/// Turn each item of a stream into a stream of its own with `f`, and produce
+/// all their items as one stream.
+///
+/// This is the stream version of [`Iterator::flat_map`]. It works through the
+/// inner streams one at a time, in order: it produces every item from the
+/// first item’s stream, then every item from the second item’s stream, and so
+/// on, so a slow inner stream holds up everything after it. (Compare [`merge`],
+/// which takes items from whichever stream is ready first.)
+pub fn flat_map<S, S2, F>(stream: S, f: F) -> impl Stream<Item = S2::Item>
+where
+    S: Stream,
+    S2: Stream,
+    F: FnMut(S::Item) -> S2,
+{
+    futures::StreamExt::flat_map(stream, f)
+}
+
+/// Run the futures from a stream of futures, up to `n` of them at a time, and
+/// produce their outputs.
+///
+/// This is how to get *bounded* concurrency: instead of awaiting each future
+/// before starting the next, or starting all of them at once, this keeps `n` of
+/// them running, and starts another one each time one finishes. The outputs
+/// come out in the order the futures *finish*, not the order they came in, so
+/// a quick future is never held up waiting for a slow one.
+pub fn buffer_unordered<S, Fut, T>(stream: S, n: usize) -> impl Stream<Item = T>
+where
+    S: Stream<Item = Fut>,
+    Fut: Future<Output = T>,
+{
+    futures::StreamExt::buffer_unordered(stream, n)
+}
+
+/// Pair each item of a stream with its index, counting up from `0`.
+///
+/// This is the stream version of [`Iterator::enumerate`]. It works nicely with
+/// [`merge`]: enumerate each stream before merging them, and you can still tell
+/// where each item came from, and in what order.
+pub fn enumerate<S: Stream>(stream: S) -> impl Stream<Item = (usize, S::Item)> {
+    futures::StreamExt::enumerate(stream)
+}
+
+/// Wrap a stream so you can look at its next item without taking it out.
+///
+/// The [`Peekable`] it returns has a `peek` method, which waits for the next
+/// item and gives you a reference to it; the item is still there the next time
+/// you ask the stream for one. That is handy for things like parsing, where
+/// what to do with the current item depends on what comes after it.
+///
+/// Like the fused futures described on [`FutureExt`], `peek` needs the stream
+/// pinned, because it holds onto the peeked item between calls. Use [`pin!`]
+/// on the `Peekable` first, and then call `peek` through `as_mut()`.
+pub fn peekable<S: Stream>(stream: S) -> Peekable<S> {
+    futures::StreamExt::peekable(stream)
+}
+
+/// Produce only the first `n` items of a stream, and then end.
+///
+/// This is the usual way to make an endless stream, like [`interval_stream`],
+/// into one which finishes. If the stream ends before producing `n` items, so
+/// does this one.
+pub fn take<S: Stream>(stream: S, n: usize) -> impl Stream<Item = S::Item> {
+    stream.take(n)
+}
+
+/// Drop the first `n` items of a stream, and produce the rest.
+///
+/// This is handy for skipping over “warm-up” items you do not care about, such
+/// as the first tick of an [`interval_stream`], which happens right away.
+pub fn skip<S: Stream>(stream: S, n: usize) -> impl Stream<Item = S::Item> {
+    stream.skip(n)
+}
+
+/// Replay a stream over and over, forever.
+///
+/// Each time the stream ends, this starts again from a fresh copy of it, which
+/// is why the stream has to implement [`Clone`]. Not every stream does: you
+/// cannot clone a channel’s receiver or the stream from [`stream_from_iter`],
+/// but you can clone the otherwise identical one from
+/// [`futures::stream::iter`].
+///
+/// Since the result never ends, use [`take`] to get a limited number of items
+/// out of it. The one exception is a stream which produces nothing at all: if
+/// a whole pass through it ends without a single item, starting over would
+/// just spin in place, so the cycled stream ends instead.
+pub fn cycle<S>(stream: S) -> impl Stream<Item = S::Item>
+where
+    S: Stream + Clone,
+    S::Item: Clone,
+{
+    let current = Box::pin(stream.clone());
+    futures::stream::unfold(
+        (stream, current, false),
+        |(stream, mut current, mut produced)| async move {
+            loop {
+                match current.next().await {
+                    Some(item) => return Some((item, (stream, current, true))),
+                    None if produced => {
+                        current = Box::pin(stream.clone());
+                        produced = false;
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
+/// Group the items of a stream into `Vec`s of `size` items each.
+///
+/// Each chunk waits until it has `size` items. If the stream ends partway
+/// through a chunk, the items collected so far still come out, as one last,
+/// shorter chunk, so no items are lost. (The final chunk is never empty,
+/// though: if the stream ends right after a full chunk, that is the end.)
+///
+/// # Panics
+///
+/// Panics if `size` is zero.
+pub fn chunks<S: Stream>(
+    stream: S,
+    size: usize,
+) -> impl Stream<Item = Vec<S::Item>> {
+    futures::StreamExt::chunks(stream, size)
+}
+
+/// Require each item of a stream to arrive within `per_item` of the one before.
+///
+/// Items which arrive in time come out as `Ok(item)`. If the next item takes
+/// longer than `per_item`, the stream produces an `Err` with the `Duration` it
+/// waited. A timeout does *not* end the stream: it keeps waiting for the late
+/// item, and produces it as `Ok` whenever it does show up.
+///
+/// Each gap produces only *one* `Err`, however long it lasts. After reporting
+/// a timeout, the stream waits for the next item with no time limit at all,
+/// and only starts timing again once that item has arrived.
+pub fn timeout_stream<S: Stream>(
+    stream: S,
+    per_item: Duration,
+) -> impl Stream<Item = Result<S::Item, Duration>> {
+    stream
+        .timeout(per_item)
+        .map(move |result| result.map_err(|_elapsed| per_item))
+}
+
 /// Fetch data from a URL. For more convenient use in _The Rust Programming
 /// Language_, panics instead of returning a [`Result`] if the request fails.
 pub async fn get(url: &str) -> Response {
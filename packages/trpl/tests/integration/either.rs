@@ -0,0 +1,629 @@
+//! Tests for the methods and trait implementations on [`trpl::Either`].
+
+use std::{error::Error, fmt, num::ParseIntError, str::Utf8Error};
+
+use trpl::Either;
+
+#[test]
+fn is_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert!(left.is_left());
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert!(!right.is_left());
+}
+
+#[test]
+fn is_right() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert!(!left.is_right());
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert!(right.is_right());
+}
+
+#[test]
+fn contains_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert!(left.contains_left(&1));
+    assert!(!left.contains_left(&2));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert!(!right.contains_left(&1));
+}
+
+#[test]
+fn contains_right() {
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert!(right.contains_right(&"hello"));
+    assert!(!right.contains_right(&"goodbye"));
+
+    let left: Either<i32, &str> = Either::Left(1);
+    assert!(!left.contains_right(&"hello"));
+}
+
+#[test]
+fn as_ref() {
+    let left: Either<String, Vec<i32>> = Either::Left(String::from("hello"));
+    assert_eq!(left.as_ref(), Either::Left(&String::from("hello")));
+    assert_eq!(left, Either::Left(String::from("hello")));
+
+    let right: Either<String, Vec<i32>> = Either::Right(vec![1, 2, 3]);
+    assert_eq!(right.as_ref(), Either::Right(&vec![1, 2, 3]));
+    assert_eq!(right, Either::Right(vec![1, 2, 3]));
+}
+
+#[test]
+fn as_mut() {
+    let mut left: Either<i32, String> = Either::Left(1);
+    if let Either::Left(n) = left.as_mut() {
+        *n += 1;
+    }
+    assert_eq!(left, Either::Left(2));
+
+    let mut right: Either<i32, String> = Either::Right(String::from("hello"));
+    if let Either::Right(s) = right.as_mut() {
+        s.push_str(", world");
+    }
+    assert_eq!(right, Either::Right(String::from("hello, world")));
+}
+
+#[test]
+fn cloned() {
+    let left: Either<String, Vec<i32>> = Either::Left(String::from("hello"));
+    assert_eq!(left.as_ref().cloned(), left);
+
+    let right: Either<String, Vec<i32>> = Either::Right(vec![1, 2, 3]);
+    assert_eq!(right.as_ref().cloned(), right);
+}
+
+#[test]
+fn map_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.map_left(|n| n + 1), Either::Left(2));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.map_left(|n| n + 1), Either::Right("hello"));
+}
+
+#[test]
+fn map_right() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.map_right(|s| s.len()), Either::Left(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.map_right(|s| s.len()), Either::Right(5));
+}
+
+#[test]
+fn ok_or_right() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.ok_or_right(), Ok(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.ok_or_right(), Err("hello"));
+}
+
+#[test]
+fn left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.left(), Some(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.left(), None);
+}
+
+#[test]
+fn right() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.right(), None);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.right(), Some("hello"));
+}
+
+#[test]
+fn iter_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.iter_left().collect::<Vec<_>>(), vec![&1]);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.iter_left().count(), 0);
+}
+
+#[test]
+fn iter_right() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.iter_right().count(), 0);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.iter_right().collect::<Vec<_>>(), vec![&"hello"]);
+}
+
+#[test]
+fn iter_left_and_right_with_flat_map() {
+    let outcomes: Vec<Either<i32, &str>> =
+        vec![Either::Left(1), Either::Right("a"), Either::Left(2)];
+
+    let lefts: Vec<_> = outcomes.iter().flat_map(Either::iter_left).collect();
+    assert_eq!(lefts, vec![&1, &2]);
+
+    let rights: Vec<_> = outcomes.iter().flat_map(Either::iter_right).collect();
+    assert_eq!(rights, vec![&"a"]);
+}
+
+#[test]
+fn left_or() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.left_or(10), 1);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.left_or(10), 10);
+}
+
+#[test]
+fn left_or_default() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.left_or_default(), 1);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.left_or_default(), 0);
+}
+
+#[test]
+fn left_or_else() {
+    let left: Either<usize, &str> = Either::Left(1);
+    assert_eq!(left.left_or_else(str::len), 1);
+
+    let right: Either<usize, &str> = Either::Right("hello");
+    assert_eq!(right.left_or_else(str::len), 5);
+}
+
+#[test]
+fn unwrap_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.unwrap_left(), 1);
+}
+
+#[test]
+#[should_panic(expected = "called `Either::unwrap_left` on a `Right` value")]
+fn unwrap_left_on_right() {
+    let right: Either<i32, &str> = Either::Right("hello");
+    right.unwrap_left();
+}
+
+#[test]
+fn unwrap_right() {
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.unwrap_right(), "hello");
+}
+
+#[test]
+#[should_panic(expected = "called `Either::unwrap_right` on a `Left` value")]
+fn unwrap_right_on_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    left.unwrap_right();
+}
+
+#[test]
+fn expect_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.expect_left("should be a number"), 1);
+}
+
+#[test]
+#[should_panic(expected = "should be a number")]
+fn expect_left_on_right() {
+    let right: Either<i32, &str> = Either::Right("hello");
+    right.expect_left("should be a number");
+}
+
+#[test]
+fn expect_right() {
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.expect_right("should be text"), "hello");
+}
+
+#[test]
+#[should_panic(expected = "should be text")]
+fn expect_right_on_left() {
+    let left: Either<i32, &str> = Either::Left(1);
+    left.expect_right("should be text");
+}
+
+#[test]
+fn map_both() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.map_both(|n| n * 10, |s| s.len()), Either::Left(10));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.map_both(|n| n * 10, |s| s.len()), Either::Right(5));
+}
+
+#[test]
+fn and_then_left() {
+    fn halve(n: i32) -> Either<i32, &'static str> {
+        if n % 2 == 0 {
+            Either::Left(n / 2)
+        } else {
+            Either::Right("odd")
+        }
+    }
+
+    let left: Either<i32, &str> = Either::Left(4);
+    assert_eq!(left.and_then_left(halve), Either::Left(2));
+
+    let left: Either<i32, &str> = Either::Left(3);
+    assert_eq!(left.and_then_left(halve), Either::Right("odd"));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.and_then_left(halve), Either::Right("hello"));
+}
+
+#[test]
+fn and() {
+    let left: Either<i32, &str> = Either::Left(1);
+    let right: Either<i32, &str> = Either::Right("first");
+    let other_left: Either<char, &str> = Either::Left('a');
+    let other_right: Either<char, &str> = Either::Right("second");
+
+    assert_eq!(left.and(other_left), Either::Left('a'));
+    assert_eq!(left.and(other_right), Either::Right("second"));
+    assert_eq!(right.and(other_left), Either::Right("first"));
+    assert_eq!(right.and(other_right), Either::Right("first"));
+}
+
+#[test]
+fn or() {
+    let left: Either<i32, &str> = Either::Left(1);
+    let right: Either<i32, &str> = Either::Right("first");
+    let other_left: Either<i32, char> = Either::Left(2);
+    let other_right: Either<i32, char> = Either::Right('b');
+
+    assert_eq!(left.or(other_left), Either::Left(1));
+    assert_eq!(left.or(other_right), Either::Left(1));
+    assert_eq!(right.or(other_left), Either::Left(2));
+    assert_eq!(right.or(other_right), Either::Right('b'));
+}
+
+#[test]
+fn either() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.either(|n| n * 10, |s| s.len() as i32), 10);
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.either(|n| n * 10, |s| s.len() as i32), 5);
+}
+
+#[test]
+fn apply() {
+    async fn greet(name: &str) -> String {
+        trpl::yield_now().await;
+        format!("Hello, {name}!")
+    }
+
+    async fn count(n: u32) -> String {
+        trpl::yield_now().await;
+        format!("Counted to {n}")
+    }
+
+    let left: Either<&str, u32> = Either::Left("Ferris");
+    let result = trpl::block_on(left.apply(greet, count));
+    assert_eq!(result, "Hello, Ferris!");
+
+    let right: Either<&str, u32> = Either::Right(3);
+    let result = trpl::block_on(right.apply(greet, count));
+    assert_eq!(result, "Counted to 3");
+}
+
+#[test]
+fn map_left_async() {
+    let mut calls = 0;
+    let mut double = |n: i32| {
+        calls += 1;
+        async move {
+            trpl::yield_now().await;
+            n * 2
+        }
+    };
+
+    let left: Either<i32, &str> = Either::Left(21);
+    let result = trpl::block_on(left.map_left_async(&mut double));
+    assert_eq!(result, Either::Left(42));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    let result = trpl::block_on(right.map_left_async(&mut double));
+    assert_eq!(result, Either::Right("hello"));
+
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn map_right_async() {
+    let mut calls = 0;
+    let mut length = |s: &str| {
+        calls += 1;
+        let length = s.len();
+        async move {
+            trpl::yield_now().await;
+            length
+        }
+    };
+
+    let left: Either<i32, &str> = Either::Left(1);
+    let result = trpl::block_on(left.map_right_async(&mut length));
+    assert_eq!(result, Either::Left(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    let result = trpl::block_on(right.map_right_async(&mut length));
+    assert_eq!(result, Either::Right(5));
+
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn flip() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.flip(), Either::Right(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.flip(), Either::Left("hello"));
+}
+
+#[test]
+fn from_result() {
+    let ok: Result<i32, &str> = Ok(1);
+    assert_eq!(Either::from_result(ok), Either::Left(1));
+
+    let err: Result<i32, &str> = Err("hello");
+    assert_eq!(Either::from_result(err), Either::Right("hello"));
+}
+
+#[test]
+fn into_result() {
+    let left: Either<i32, &str> = Either::Left(1);
+    assert_eq!(left.into_result(), Ok(1));
+
+    let right: Either<i32, &str> = Either::Right("hello");
+    assert_eq!(right.into_result(), Err("hello"));
+}
+
+#[test]
+fn from_and_into_result() {
+    let ok: Result<i32, &str> = Ok(1);
+    let left: Either<i32, &str> = ok.into();
+    assert_eq!(left, Either::Left(1));
+    assert_eq!(Result::from(left), ok);
+
+    let err: Result<i32, &str> = Err("hello");
+    let right: Either<i32, &str> = Either::from(err);
+    assert_eq!(right, Either::Right("hello"));
+    let round_tripped: Result<i32, &str> = right.into();
+    assert_eq!(round_tripped, err);
+}
+
+#[test]
+fn from_and_into_futures_either() {
+    let ours: Either<i32, &str> = futures::future::Either::Left(1).into();
+    assert_eq!(ours, Either::Left(1));
+    let theirs: futures::future::Either<i32, &str> = ours.into();
+    assert!(matches!(theirs, futures::future::Either::Left(1)));
+
+    let ours = Either::from(futures::future::Either::<i32, _>::Right("hello"));
+    assert_eq!(ours, Either::Right("hello"));
+    let theirs = futures::future::Either::from(ours);
+    assert!(matches!(theirs, futures::future::Either::Right("hello")));
+}
+
+#[test]
+fn into_inner() {
+    let left: Either<i32, i32> = Either::Left(1);
+    assert_eq!(left.into_inner(), 1);
+
+    let right: Either<i32, i32> = Either::Right(2);
+    assert_eq!(right.into_inner(), 2);
+}
+
+#[test]
+fn into_iter() {
+    let left: Either<i32, i32> = Either::Left(1);
+    assert_eq!(left.into_iter().collect::<Vec<_>>(), vec![1]);
+
+    let right: Either<i32, i32> = Either::Right(2);
+    let mut seen = vec![];
+    for n in right {
+        seen.push(n);
+    }
+    assert_eq!(seen, vec![2]);
+}
+
+#[test]
+fn iter() {
+    let left: Either<String, String> = Either::Left(String::from("a"));
+    assert_eq!(left.iter().collect::<Vec<_>>(), vec!["a"]);
+
+    let right: Either<String, String> = Either::Right(String::from("b"));
+    assert_eq!(right.iter().map(|s| s.len()).sum::<usize>(), 1);
+}
+
+#[test]
+fn map_inner() {
+    let left: Either<&str, &str> = Either::Left("hello");
+    assert_eq!(left.map_inner(str::len), Either::Left(5));
+
+    let right: Either<&str, &str> = Either::Right("hi");
+    assert_eq!(right.map_inner(str::len), Either::Right(2));
+}
+
+#[test]
+fn normalize() {
+    let left: Either<&str, &str> = Either::Left("preferred");
+    assert_eq!(left.normalize(), ("preferred", false));
+
+    let right: Either<&str, &str> = Either::Right("fallback");
+    assert_eq!(right.normalize(), ("fallback", true));
+}
+
+#[test]
+fn flatten() {
+    let left_left: Either<Either<i32, &str>, &str> =
+        Either::Left(Either::Left(1));
+    assert_eq!(left_left.flatten(), Either::Left(1));
+
+    let left_right: Either<Either<i32, &str>, &str> =
+        Either::Left(Either::Right("inner"));
+    assert_eq!(left_right.flatten(), Either::Right("inner"));
+
+    let right: Either<Either<i32, &str>, &str> = Either::Right("outer");
+    assert_eq!(right.flatten(), Either::Right("outer"));
+}
+
+#[test]
+fn transpose() {
+    let left_some: Either<Option<i32>, &str> = Either::Left(Some(1));
+    assert_eq!(left_some.transpose(), Some(Either::Left(1)));
+
+    let left_none: Either<Option<i32>, &str> = Either::Left(None);
+    assert_eq!(left_none.transpose(), None);
+
+    let right: Either<Option<i32>, &str> = Either::Right("hello");
+    assert_eq!(right.transpose(), Some(Either::Right("hello")));
+}
+
+#[test]
+fn factor_first() {
+    let left: Either<(u32, i32), (u32, &str)> = Either::Left((7, 1));
+    assert_eq!(left.factor_first(), (7, Either::Left(1)));
+
+    let right: Either<(u32, i32), (u32, &str)> = Either::Right((7, "hello"));
+    assert_eq!(right.factor_first(), (7, Either::Right("hello")));
+}
+
+#[test]
+fn factor_second() {
+    let left: Either<(i32, u32), (&str, u32)> = Either::Left((1, 7));
+    assert_eq!(left.factor_second(), (Either::Left(1), 7));
+
+    let right: Either<(i32, u32), (&str, u32)> = Either::Right(("hello", 7));
+    assert_eq!(right.factor_second(), (Either::Right("hello"), 7));
+}
+
+#[test]
+fn unzip() {
+    let left: Either<(i32, &str), (i32, &str)> = Either::Left((1, "one"));
+    assert_eq!(left.unzip(), (Either::Left(1), Either::Left("one")));
+
+    let right: Either<(i32, &str), (i32, &str)> = Either::Right((2, "two"));
+    assert_eq!(right.unzip(), (Either::Right(2), Either::Right("two")));
+}
+
+#[test]
+fn ordering() {
+    let mut outcomes: Vec<Either<i32, &str>> = vec![
+        Either::Right("b"),
+        Either::Left(2),
+        Either::Right("a"),
+        Either::Left(1),
+    ];
+    outcomes.sort();
+
+    assert_eq!(
+        outcomes,
+        vec![
+            Either::Left(1),
+            Either::Left(2),
+            Either::Right("a"),
+            Either::Right("b"),
+        ]
+    );
+    assert!(Either::<i32, i32>::Left(100) < Either::Right(0));
+}
+
+#[test]
+fn hash() {
+    use std::collections::HashSet;
+
+    let mut outcomes: HashSet<Either<i32, i32>> = HashSet::new();
+    outcomes.insert(Either::Left(1));
+    outcomes.insert(Either::Right(1));
+    outcomes.insert(Either::Left(1));
+
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.contains(&Either::Left(1)));
+    assert!(outcomes.contains(&Either::Right(1)));
+}
+
+#[test]
+fn display() {
+    let left: Either<&str, f64> = Either::Left("Hello");
+    assert_eq!(format!("{left}"), "Hello");
+
+    let right: Either<&str, f64> = Either::Right(1.5);
+    assert_eq!(format!("{right}"), "1.5");
+}
+
+#[derive(Debug)]
+struct BadNumber(ParseIntError);
+
+impl fmt::Display for BadNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bad number")
+    }
+}
+
+impl Error for BadNumber {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[derive(Debug)]
+struct BadText(Utf8Error);
+
+impl fmt::Display for BadText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bad text")
+    }
+}
+
+impl Error for BadText {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn bad_number() -> BadNumber {
+    BadNumber("nope".parse::<u8>().unwrap_err())
+}
+
+fn bad_text() -> BadText {
+    BadText(String::from_utf8(vec![0xff]).unwrap_err().utf8_error())
+}
+
+#[test]
+fn error_source_left() {
+    let left: Either<BadNumber, BadText> = Either::Left(bad_number());
+    let source = left.source().unwrap();
+    assert!(source.downcast_ref::<BadNumber>().is_some());
+    let cause = source.source().unwrap();
+    assert!(cause.downcast_ref::<ParseIntError>().is_some());
+}
+
+#[test]
+fn error_source_right() {
+    let right: Either<BadNumber, BadText> = Either::Right(bad_text());
+    let source = right.source().unwrap();
+    assert!(source.downcast_ref::<BadText>().is_some());
+    let cause = source.source().unwrap();
+    assert!(cause.downcast_ref::<Utf8Error>().is_some());
+}
+
+#[test]
+fn error_works_with_question_mark() {
+    fn fails() -> Result<(), Box<dyn Error>> {
+        let err: Either<BadNumber, BadText> = Either::Right(bad_text());
+        Err(err)?
+    }
+
+    assert_eq!(fails().unwrap_err().to_string(), "bad text");
+}
@@ -14,6 +14,8 @@ use std::{pin::Pin, time::Duration};
 use futures::Future;
 use trpl::{Either, Receiver, Sender};
 
+mod either;
+
 /// We initially named the function `run` and an online version of the async chapter
 /// was released with that name, so we want to keep it working. We decided to rename
 /// `run` to be `block_on` to more closely match other crates' names, so most of the
@@ -24,6 +26,25 @@ fn using_run_works() {
     assert_eq!(val, "Hello");
 }
 
+#[test]
+fn run_shuts_down_with_background_tasks_still_running() {
+    let start = std::time::Instant::now();
+    let val = trpl::run(async {
+        trpl::spawn_task(async {
+            loop {
+                trpl::sleep(Duration::from_millis(1)).await;
+            }
+        });
+        trpl::spawn_blocking(|| std::thread::sleep(Duration::from_secs(10)));
+
+        "Hello"
+    });
+    assert_eq!(val, "Hello");
+    // Dropping the runtime would wait for the blocking task to finish, so this
+    // only returns early because `run` stops waiting for it.
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
+
 /// This test is foundational for all the others, as they depend on `block_on`.
 ///
 /// If we mess this up, *all* the tests below will fail -- so by the same token,
@@ -36,233 +57,2137 @@ fn re_exported_block_on_works() {
 }
 
 #[test]
-fn re_exported_spawn_works() {
-    let result = trpl::block_on(async {
-        let handle_a = trpl::spawn_task(async { "Hello" });
-        let handle_b = trpl::spawn_task(async { "Goodbye" });
-        vec![handle_a.await.unwrap(), handle_b.await.unwrap()]
+fn with_runtime() {
+    let result = trpl::with_runtime(|rt| {
+        let handle = rt.spawn(async { "Hello" });
+        let greeting = rt.block_on(handle).unwrap();
+        let name = rt.block_on(async { "World" });
+        format!("{greeting}, {name}!")
     });
 
-    assert_eq!(result, vec!["Hello", "Goodbye"]);
+    assert_eq!(result, "Hello, World!");
 }
 
 #[test]
-fn re_exported_sleep_works() {
-    let val = trpl::block_on(async {
-        trpl::sleep(Duration::from_micros(1)).await;
-        "Done!"
+fn block_on_with_threads() {
+    // Spin the CPU without ever awaiting.
+    fn spin(n: u64) -> u64 {
+        (0..100_000).fold(n, |acc, i| acc.wrapping_add(i % 3))
+    }
+
+    let result = trpl::block_on_with_threads(2, async {
+        let handles: Vec<_> = (0..4)
+            .map(|n| trpl::spawn_task(async move { spin(n) }))
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
     });
-    assert_eq!(val, "Done!");
+
+    assert_eq!(result, (0..4).map(spin).collect::<Vec<_>>());
 }
 
 #[test]
-fn re_exported_channel_apis_work() {
-    trpl::block_on(async {
-        // Explicitly naming the type to confirm the re-exports are aligned.
-        let (tx, mut rx): (Sender<&str>, Receiver<&str>) = trpl::channel();
+fn try_block_on() {
+    let result = trpl::try_block_on(async { "Hello" });
+    assert_eq!(result.unwrap(), "Hello");
+}
 
-        tx.send("Hello").unwrap();
+#[test]
+fn block_on_shared_works_repeatedly() {
+    let first = trpl::block_on_shared(async { "Hello" });
+    let second = trpl::block_on_shared(async {
         trpl::sleep(Duration::from_millis(1)).await;
-        tx.send("Goodbye").unwrap();
-        drop(tx);
-
-        assert_eq!(rx.recv().await, Some("Hello"));
-        assert_eq!(rx.recv().await, Some("Goodbye"));
-        assert_eq!(rx.recv().await, None);
+        "Goodbye"
     });
+
+    assert_eq!((first, second), ("Hello", "Goodbye"));
 }
 
-mod re_exported_join_apis_work {
+mod block_on_timeout {
     use super::*;
 
     #[test]
-    fn join_fn() {
-        let result = trpl::block_on(async {
-            let a = async { 1 };
-            let b = async { 2 };
-            trpl::join(a, b).await
-        });
-
-        assert_eq!(result, (1, 2));
+    fn completes_in_time() {
+        let result =
+            trpl::block_on_timeout(Duration::from_secs(1), async { "Hello" });
+        assert_eq!(result, Ok("Hello"));
     }
 
     #[test]
-    fn join3_fn() {
-        let result = trpl::block_on(async {
-            let a = async { 1 };
-            let b = async { 2 };
-            let c = async { 3 };
-
-            trpl::join3(a, b, c).await
+    fn times_out() {
+        let max = Duration::from_millis(1);
+        let result = trpl::block_on_timeout(max, async {
+            trpl::sleep(Duration::from_secs(1)).await;
+            "Hello"
         });
+        assert_eq!(result, Err(max));
+    }
+}
 
-        assert_eq!(result, (1, 2, 3));
+#[test]
+fn block_on_until_ctrl_c_returns_output_without_signal() {
+    let result = trpl::block_on_until_ctrl_c(async {
+        trpl::sleep(Duration::from_millis(5)).await;
+        "Hello"
+    });
+    assert_eq!(result, Some("Hello"));
+}
+
+#[test]
+fn block_on_all() {
+    use std::cell::RefCell;
+
+    let log = RefCell::new(Vec::new());
+    let delays = [30, 10, 20];
+    let futures = delays
+        .into_iter()
+        .map(|ms| {
+            let log = &log;
+            async move {
+                log.borrow_mut().push(format!("start {ms}"));
+                trpl::sleep(Duration::from_millis(ms)).await;
+                log.borrow_mut().push(format!("end {ms}"));
+                ms
+            }
+        })
+        .collect();
+
+    let result = trpl::block_on_all(futures);
+    assert_eq!(result, vec![30, 10, 20]);
+
+    // Running them one after another would finish each before starting the
+    // next; running them concurrently starts all of them first.
+    let log = log.into_inner();
+    assert_eq!(log[..3], ["start 30", "start 10", "start 20"]);
+}
+
+#[test]
+fn re_exported_spawn_works() {
+    let result = trpl::block_on(async {
+        let handle_a = trpl::spawn_task(async { "Hello" });
+        let handle_b = trpl::spawn_task(async { "Goodbye" });
+        vec![handle_a.await.unwrap(), handle_b.await.unwrap()]
+    });
+
+    assert_eq!(result, vec!["Hello", "Goodbye"]);
+}
+
+mod join_task {
+    use super::*;
+
+    use trpl::JoinHandle;
+
+    fn spawn_greeting(name: &'static str) -> JoinHandle<String> {
+        trpl::spawn_task(async move { format!("Hello, {name}!") })
     }
 
     #[test]
-    fn join_all_fn() {
+    fn returns_output() {
         let result = trpl::block_on(async {
-            let a = async { format!("{}", 1) };
+            trpl::join_task(spawn_greeting("Ferris")).await
+        });
 
-            let b = async { "Hello".to_string() };
+        assert_eq!(result, "Hello, Ferris!");
+    }
 
-            let outer = String::from("World");
-            let c = async move { outer.to_string() };
+    #[test]
+    #[should_panic(expected = "oh no")]
+    fn propagates_panics() {
+        trpl::block_on(async {
+            trpl::join_task(trpl::spawn_task(async { panic!("oh no") })).await
+        });
+    }
 
-            let futures: Vec<Pin<Box<dyn Future<Output = String>>>> =
-                vec![Box::pin(a), Box::pin(b), Box::pin(c)];
+    #[test]
+    #[should_panic(expected = "the task was aborted")]
+    fn panics_when_aborted() {
+        trpl::block_on(async {
+            let handle = trpl::spawn_task(trpl::sleep(Duration::from_secs(1)));
+            handle.abort();
+            trpl::join_task(handle).await
+        });
+    }
+}
 
-            trpl::join_all(futures).await
+#[test]
+fn spawn_detached_runs_the_task() {
+    let result = trpl::block_on(async {
+        let (tx, mut rx) = trpl::channel();
+        trpl::spawn_detached(async move {
+            trpl::sleep(Duration::from_millis(1)).await;
+            tx.send("Hello from the background").unwrap();
         });
 
-        assert_eq!(
-            result,
-            vec![
-                String::from("1"),
-                String::from("Hello"),
-                String::from("World")
-            ]
-        );
-    }
+        rx.recv().await
+    });
+
+    assert_eq!(result, Some("Hello from the background"));
+}
 
+mod spawn_task_named {
     #[test]
-    fn join_macro() {
+    fn returns_output() {
         let result = trpl::block_on(async {
-            let a = async { 1 };
-            let b = async { "Hello" };
+            trpl::spawn_task_named("greeter", async { "Hello" }).await
+        });
 
-            let outer = vec![String::from("World")];
-            let c = async move { outer };
+        assert_eq!(result.unwrap(), "Hello");
+    }
 
-            trpl::join!(a, b, c)
+    #[test]
+    #[should_panic(expected = "task `downloader` panicked: oh no")]
+    fn panic_message_includes_name() {
+        trpl::block_on(async {
+            let handle = trpl::spawn_task_named("downloader", async {
+                trpl::yield_now().await;
+                panic!("oh no");
+            });
+            trpl::join_task(handle).await
         });
-
-        assert_eq!(result, (1, "Hello", vec![String::from("World")]));
     }
 }
 
 #[test]
-fn select() {
-    #[derive(Debug, PartialEq)]
-    struct Slow;
+fn re_exported_spawn_blocking_works() {
+    let result = trpl::block_on(async {
+        let handle = trpl::spawn_blocking(|| (1..=10).sum::<u32>());
+        handle.await.unwrap()
+    });
 
-    #[derive(Debug, PartialEq)]
-    struct Fast;
+    assert_eq!(result, 55);
+}
 
-    let val = trpl::block_on(async {
-        let slow = async {
-            trpl::sleep(Duration::from_millis(1_000)).await;
-            Slow
-        };
+#[test]
+fn re_exported_spawn_local_works() {
+    use std::{cell::RefCell, rc::Rc};
+    use trpl::LocalSet;
 
-        let fast = async {
-            trpl::sleep(Duration::from_millis(1)).await;
-            Fast
-        };
+    let seen = trpl::block_on(async {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        LocalSet::new()
+            .run_until({
+                let seen = Rc::clone(&seen);
+                async move {
+                    let handle = trpl::spawn_local(async move {
+                        trpl::yield_now().await;
+                        seen.borrow_mut().push("Hello from a local task");
+                    });
+                    handle.await.unwrap();
+                }
+            })
+            .await;
+        Rc::try_unwrap(seen).unwrap().into_inner()
+    });
 
-        trpl::select(slow, fast).await
+    assert_eq!(seen, vec!["Hello from a local task"]);
+}
+
+#[test]
+fn re_exported_sleep_works() {
+    let val = trpl::block_on(async {
+        trpl::sleep(Duration::from_micros(1)).await;
+        "Done!"
     });
+    assert_eq!(val, "Done!");
+}
 
-    assert!(matches!(val, Either::Right(Fast)));
+#[test]
+fn sleep_until_works() {
+    use trpl::Instant;
+
+    let deadline = Instant::now() + Duration::from_millis(5);
+    trpl::block_on(trpl::sleep_until(deadline));
+    assert!(Instant::now() >= deadline);
 }
 
 #[test]
-fn race_continues_to_work() {
-    #[derive(Debug, PartialEq)]
-    struct Slow;
+fn now_works_as_sleep_until_deadline() {
+    let deadline = trpl::now() + Duration::from_millis(5);
+    trpl::block_on(trpl::sleep_until(deadline));
+    assert!(trpl::now() >= deadline);
+}
 
-    #[derive(Debug, PartialEq)]
-    struct Fast;
+#[test]
+fn sleep_until_works_with_std_instant() {
+    let deadline = std::time::Instant::now() + Duration::from_millis(5);
+    trpl::block_on(trpl::sleep_until(deadline.into()));
+    assert!(std::time::Instant::now() >= deadline);
+}
 
-    let val = trpl::block_on(async {
-        let slow = async {
-            trpl::sleep(Duration::from_millis(1_000)).await;
-            Slow
-        };
+#[test]
+fn sleep_random() {
+    let min = Duration::from_millis(10);
+    let max = Duration::from_millis(30);
 
-        let fast = async {
-            trpl::sleep(Duration::from_millis(1)).await;
-            Fast
-        };
+    let start = std::time::Instant::now();
+    trpl::block_on(trpl::sleep_random(min, max));
+    let elapsed = start.elapsed();
 
-        trpl::race(slow, fast).await
-    });
+    assert!(elapsed >= min);
+    // Timers never fire early, but on a busy machine they can fire late, so
+    // leave plenty of slack at the top.
+    assert!(elapsed < max + Duration::from_millis(250));
+}
 
-    assert!(matches!(val, Either::Right(Fast)));
+#[test]
+#[should_panic(
+    expected = "called `sleep_random` with `min` greater than `max`"
+)]
+fn sleep_random_with_min_greater_than_max() {
+    trpl::block_on(trpl::sleep_random(
+        Duration::from_millis(2),
+        Duration::from_millis(1),
+    ));
 }
 
 #[test]
-fn yield_now() {
-    let result = trpl::block_on(async {
-        trpl::yield_now().await;
-        "done"
+fn elapsed() {
+    let nap = Duration::from_millis(10);
+    let (output, took) = trpl::block_on(trpl::elapsed(async {
+        trpl::sleep(nap).await;
+        "Hello"
+    }));
+
+    assert_eq!(output, "Hello");
+    assert!(took >= nap);
+}
+
+#[test]
+fn re_exported_channel_apis_work() {
+    trpl::block_on(async {
+        // Explicitly naming the type to confirm the re-exports are aligned.
+        let (tx, mut rx): (Sender<&str>, Receiver<&str>) = trpl::channel();
+
+        tx.send("Hello").unwrap();
+        trpl::sleep(Duration::from_millis(1)).await;
+        tx.send("Goodbye").unwrap();
+        drop(tx);
+
+        assert_eq!(rx.recv().await, Some("Hello"));
+        assert_eq!(rx.recv().await, Some("Goodbye"));
+        assert_eq!(rx.recv().await, None);
     });
+}
+
+#[test]
+fn bounded_channel_applies_backpressure() {
+    use trpl::{BoundedReceiver, BoundedSender};
 
-    assert_eq!(result, "done");
+    trpl::block_on(async {
+        let (tx, mut rx): (BoundedSender<i32>, BoundedReceiver<i32>) =
+            trpl::bounded_channel(2);
+
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+
+        // The channel is full, so this send cannot finish yet.
+        let blocked = trpl::timeout(Duration::from_millis(10), tx.send(3));
+        assert!(blocked.await.is_err());
+
+        // Receiving makes room, so now it can.
+        assert_eq!(rx.recv().await, Some(1));
+        tx.send(3).await.unwrap();
+        drop(tx);
+
+        assert_eq!(rx.recv().await, Some(2));
+        assert_eq!(rx.recv().await, Some(3));
+        assert_eq!(rx.recv().await, None);
+    });
 }
 
 #[test]
-fn read_to_string() {
+fn oneshot_channel() {
+    use trpl::{OneshotReceiver, OneshotSender};
+
     let result = trpl::block_on(async {
-        trpl::read_to_string("tests/integration/to-read.txt")
-            .await
-            .unwrap()
+        let (tx, rx): (OneshotSender<&str>, OneshotReceiver<&str>) =
+            trpl::oneshot_channel();
+
+        trpl::spawn_task(async move {
+            trpl::sleep(Duration::from_millis(1)).await;
+            tx.send("Hello").unwrap();
+        });
+
+        rx.await
     });
 
-    assert_eq!(result, String::from("This is some text!\n"));
+    assert_eq!(result, Ok("Hello"));
 }
 
 #[test]
-fn stream_iter() {
-    use trpl::StreamExt;
+fn broadcast_channel() {
+    use trpl::{BroadcastReceiver, BroadcastSender};
 
     let result = trpl::block_on(async {
-        let ns = vec![1, 2, 3];
-        let mut stream = trpl::stream_from_iter(ns);
-        let mut result = vec![];
-        while let Some(n) = stream.next().await {
-            result.push(format!("{n}"));
-        }
-        result
+        let (tx, mut rx1): (BroadcastSender<&str>, BroadcastReceiver<&str>) =
+            trpl::broadcast_channel(4);
+        let mut rx2 = tx.subscribe();
+
+        tx.send("Hello").unwrap();
+
+        (rx1.recv().await, rx2.recv().await)
     });
 
-    assert_eq!(
-        result,
-        vec![String::from("1"), String::from("2"), String::from("3")]
-    )
+    assert_eq!(result, (Ok("Hello"), Ok("Hello")));
 }
 
 #[test]
-fn receiver_stream() {
-    use trpl::ReceiverStream;
-    use trpl::StreamExt;
+fn watch_channel() {
+    use trpl::{WatchReceiver, WatchSender};
 
-    let result: Vec<u32> = trpl::block_on(async {
-        println!("startup");
-        let (tx, rx) = trpl::channel();
-        let rx_stream = ReceiverStream::new(rx);
-        println!("sending 123");
-        tx.send(123).unwrap();
-        drop(tx); // So the receiver channel closes!
+    let (tx, _rx): (WatchSender<&str>, WatchReceiver<&str>) =
+        trpl::watch_channel("first");
 
-        rx_stream.collect().await
-    });
+    tx.send("second").unwrap();
+    tx.send("third").unwrap();
 
-    assert_eq!(result, vec![123]);
+    let late = tx.subscribe();
+    assert_eq!(*late.borrow(), "third");
 }
 
 #[test]
-fn re_exported_interval_stream_works() {
-    use trpl::{IntervalStream, StreamExt};
+fn re_exported_mutex() {
+    use std::sync::Arc;
+    use trpl::{Mutex, MutexGuard};
 
-    trpl::block_on(async {
-        let mut interval_stream =
-            IntervalStream::new(trpl::interval(Duration::from_millis(1)))
-                .take(1);
+    let result = trpl::block_on(async {
+        let counter = Arc::new(Mutex::new(0));
 
-        assert!(interval_stream.next().await.is_some());
-        assert!(interval_stream.next().await.is_none());
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                trpl::spawn_task(async move {
+                    for _ in 0..10 {
+                        let mut count: MutexGuard<i32> = counter.lock().await;
+                        // Holding the lock across an await point is fine.
+                        trpl::yield_now().await;
+                        *count += 1;
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        *counter.lock().await
+    });
+
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn re_exported_rw_lock() {
+    use trpl::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+    trpl::block_on(async {
+        let lock = RwLock::new(1);
+
+        let read_a: RwLockReadGuard<i32> = lock.read().await;
+        let read_b: RwLockReadGuard<i32> = lock.read().await;
+        assert_eq!(*read_a + *read_b, 2);
+
+        // The writer has to wait for both readers.
+        let blocked = trpl::timeout(Duration::from_millis(10), lock.write());
+        assert!(blocked.await.is_err());
+
+        drop(read_a);
+        drop(read_b);
+
+        let mut write: RwLockWriteGuard<i32> = lock.write().await;
+        *write += 1;
+        drop(write);
+
+        assert_eq!(*lock.read().await, 2);
+    });
+}
+
+#[test]
+fn re_exported_semaphore() {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use trpl::{OwnedSemaphorePermit, Semaphore};
+
+    let most_running = trpl::block_on(async {
+        let semaphore = Arc::new(Semaphore::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let most_running = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let semaphore = Arc::clone(&semaphore);
+                let running = Arc::clone(&running);
+                let most_running = Arc::clone(&most_running);
+                trpl::spawn_task(async move {
+                    let _permit: OwnedSemaphorePermit =
+                        semaphore.acquire_owned().await.unwrap();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_running.fetch_max(now, Ordering::SeqCst);
+                    trpl::sleep(Duration::from_millis(50)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        most_running.load(Ordering::SeqCst)
+    });
+
+    // Never more than the two permits, but both of them in use at once.
+    assert_eq!(most_running, 2);
+}
+
+#[test]
+fn abortable() {
+    use trpl::{AbortHandle, Aborted};
+
+    let result = trpl::block_on(async {
+        let (future, handle): (_, AbortHandle) =
+            trpl::abortable(trpl::sleep(Duration::from_secs(10)));
+
+        trpl::spawn_task(async move {
+            trpl::sleep(Duration::from_millis(1)).await;
+            handle.abort();
+        });
+
+        future.await
+    });
+
+    assert_eq!(result, Err(Aborted));
+}
+
+#[test]
+fn re_exported_cancellation_token() {
+    use trpl::CancellationToken;
+
+    let result = trpl::block_on(async {
+        let token = CancellationToken::new();
+
+        let handle = trpl::spawn_task({
+            let token = token.clone();
+            async move {
+                let mut ticks = 0;
+                loop {
+                    trpl::select! {
+                        _ = token.cancelled() => return ticks,
+                        _ = trpl::sleep(Duration::from_millis(1)) => ticks += 1,
+                    }
+                }
+            }
+        });
+
+        trpl::sleep(Duration::from_millis(10)).await;
+        token.cancel();
+        handle.await.unwrap()
+    });
+
+    assert!(result > 0);
+}
+
+#[test]
+fn collect_vec() {
+    let result = trpl::block_on(async {
+        let (tx, rx) = trpl::channel();
+        trpl::spawn_task(async move {
+            for message in ["a", "b", "c"] {
+                tx.send(message).unwrap();
+                trpl::sleep(Duration::from_millis(1)).await;
+            }
+        });
+
+        trpl::collect_vec(rx).await
+    });
+
+    assert_eq!(result, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn blocking_recv_all() {
+    let rt = trpl::Runtime::new().unwrap();
+    let (tx, rx) = trpl::channel();
+    rt.spawn(async move {
+        for message in ["a", "b", "c"] {
+            tx.send(message).unwrap();
+            trpl::sleep(Duration::from_millis(1)).await;
+        }
+    });
+
+    let messages = trpl::blocking_recv_all(&rt, rx);
+    assert_eq!(messages, vec!["a", "b", "c"]);
+}
+
+mod recv_timeout {
+    use super::*;
+
+    #[test]
+    fn receives_a_prompt_message() {
+        let result = trpl::block_on(async {
+            let (tx, mut rx) = trpl::channel();
+            tx.send("Hello").unwrap();
+            trpl::recv_timeout(&mut rx, Duration::from_secs(1)).await
+        });
+
+        assert_eq!(result, Ok(Some("Hello")));
+    }
+
+    #[test]
+    fn senders_dropped() {
+        let result = trpl::block_on(async {
+            let (tx, mut rx) = trpl::channel::<&str>();
+            drop(tx);
+            trpl::recv_timeout(&mut rx, Duration::from_secs(1)).await
+        });
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn times_out() {
+        let max = Duration::from_millis(1);
+        let result = trpl::block_on(async {
+            let (_tx, mut rx) = trpl::channel::<&str>();
+            trpl::recv_timeout(&mut rx, max).await
+        });
+
+        assert_eq!(result, Err(max));
+    }
+}
+
+mod timeout_collecting {
+    use super::*;
+
+    #[test]
+    fn keeps_messages_from_before_the_deadline() {
+        let result = trpl::block_on(async {
+            let (tx, rx) = trpl::channel();
+            trpl::spawn_detached(async move {
+                tx.send(1).unwrap();
+                tx.send(2).unwrap();
+                trpl::sleep(Duration::from_millis(500)).await;
+                let _ = tx.send(3);
+            });
+
+            trpl::timeout_collecting(Duration::from_millis(50), rx).await
+        });
+
+        assert_eq!(result, vec![1, 2]);
+    }
+
+    #[test]
+    fn stops_once_senders_are_dropped() {
+        let result = trpl::block_on(async {
+            let (tx, rx) = trpl::channel();
+            tx.send("Hello").unwrap();
+            drop(tx);
+            trpl::timeout_collecting(Duration::from_secs(10), rx).await
+        });
+
+        assert_eq!(result, vec!["Hello"]);
+    }
+}
+
+#[test]
+fn select_loop() {
+    let mut received = Vec::new();
+    trpl::block_on(async {
+        let (tx_a, rx_a) = trpl::channel();
+        let (tx_b, rx_b) = trpl::channel();
+
+        trpl::spawn_task(async move {
+            for message in ["a1", "a2", "a3"] {
+                tx_a.send(message).unwrap();
+                trpl::sleep(Duration::from_millis(2)).await;
+            }
+        });
+        trpl::spawn_task(async move {
+            for message in ["b1", "b2"] {
+                trpl::sleep(Duration::from_millis(1)).await;
+                tx_b.send(message).unwrap();
+            }
+        });
+
+        trpl::select_loop(|message| received.push(message), rx_a, rx_b).await;
+    });
+
+    received.sort();
+    assert_eq!(received, vec!["a1", "a2", "a3", "b1", "b2"]);
+}
+
+mod re_exported_join_apis_work {
+    use super::*;
+
+    #[test]
+    fn join_fn() {
+        let result = trpl::block_on(async {
+            let a = async { 1 };
+            let b = async { 2 };
+            trpl::join(a, b).await
+        });
+
+        assert_eq!(result, (1, 2));
+    }
+
+    #[test]
+    fn join3_fn() {
+        let result = trpl::block_on(async {
+            let a = async { 1 };
+            let b = async { 2 };
+            let c = async { 3 };
+
+            trpl::join3(a, b, c).await
+        });
+
+        assert_eq!(result, (1, 2, 3));
+    }
+
+    #[test]
+    fn join4_fn() {
+        let result = trpl::block_on(async {
+            let a = async { 1 };
+            let b = async { "two" };
+            let c = async { 3.0 };
+            let d = async { '4' };
+
+            trpl::join4(a, b, c, d).await
+        });
+
+        assert_eq!(result, (1, "two", 3.0, '4'));
+    }
+
+    #[test]
+    fn join5_fn() {
+        let result = trpl::block_on(async {
+            let a = async { 1 };
+            let b = async { "two" };
+            let c = async { 3.0 };
+            let d = async { '4' };
+            let e = async { String::from("five") };
+
+            trpl::join5(a, b, c, d, e).await
+        });
+
+        assert_eq!(result, (1, "two", 3.0, '4', String::from("five")));
+    }
+
+    #[test]
+    fn try_join_fn_all_ok() {
+        let result: Result<(i32, &str), String> = trpl::block_on(async {
+            let a = async { Ok(1) };
+            let b = async { Ok("two") };
+
+            trpl::try_join(a, b).await
+        });
+
+        assert_eq!(result, Ok((1, "two")));
+    }
+
+    #[test]
+    fn try_join_fn_short_circuits() {
+        let result: Result<(i32, &str), String> = trpl::block_on(async {
+            let a = async {
+                trpl::sleep(Duration::from_secs(1)).await;
+                Ok(1)
+            };
+            let b = async { Err(String::from("oh no")) };
+
+            trpl::try_join(a, b).await
+        });
+
+        assert_eq!(result, Err(String::from("oh no")));
+    }
+
+    #[test]
+    fn join_all_fn() {
+        let result = trpl::block_on(async {
+            let a = async { format!("{}", 1) };
+
+            let b = async { "Hello".to_string() };
+
+            let outer = String::from("World");
+            let c = async move { outer.to_string() };
+
+            let futures: Vec<Pin<Box<dyn Future<Output = String>>>> =
+                vec![Box::pin(a), Box::pin(b), Box::pin(c)];
+
+            trpl::join_all(futures).await
+        });
+
+        assert_eq!(
+            result,
+            vec![
+                String::from("1"),
+                String::from("Hello"),
+                String::from("World")
+            ]
+        );
+    }
+
+    #[test]
+    fn join_macro() {
+        let result = trpl::block_on(async {
+            let a = async { 1 };
+            let b = async { "Hello" };
+
+            let outer = vec![String::from("World")];
+            let c = async move { outer };
+
+            trpl::join!(a, b, c)
+        });
+
+        assert_eq!(result, (1, "Hello", vec![String::from("World")]));
+    }
+}
+
+#[test]
+fn join_all_settled() {
+    let result = trpl::block_on(async {
+        let futures = [(20, true), (1, false), (10, true)]
+            .into_iter()
+            .map(|(ms, succeeds)| async move {
+                trpl::sleep(Duration::from_millis(ms)).await;
+                if succeeds { Ok(ms) } else { Err(ms) }
+            })
+            .collect();
+
+        trpl::join_all_settled(futures).await
+    });
+
+    assert_eq!(result, vec![Ok(20), Err(1), Ok(10)]);
+}
+
+#[test]
+fn select() {
+    #[derive(Debug, PartialEq)]
+    struct Slow;
+
+    #[derive(Debug, PartialEq)]
+    struct Fast;
+
+    let val = trpl::block_on(async {
+        let slow = async {
+            trpl::sleep(Duration::from_millis(1_000)).await;
+            Slow
+        };
+
+        let fast = async {
+            trpl::sleep(Duration::from_millis(1)).await;
+            Fast
+        };
+
+        trpl::select(slow, fast).await
+    });
+
+    assert!(matches!(val, Either::Right(Fast)));
+}
+
+#[test]
+fn select_macro() {
+    let val = trpl::block_on(async {
+        let (tx, mut rx) = trpl::channel();
+        let (_other_tx, mut other_rx) = trpl::channel();
+
+        trpl::spawn_task(async move {
+            trpl::sleep(Duration::from_millis(1)).await;
+            tx.send("Hello").unwrap();
+        });
+
+        trpl::select! {
+            Some(message) = rx.recv() => message,
+            Some(message) = other_rx.recv() => message,
+            _ = trpl::sleep(Duration::from_secs(1)) => "timed out",
+        }
+    });
+
+    assert_eq!(val, "Hello");
+}
+
+#[test]
+fn race_continues_to_work() {
+    #[derive(Debug, PartialEq)]
+    struct Slow;
+
+    #[derive(Debug, PartialEq)]
+    struct Fast;
+
+    let val = trpl::block_on(async {
+        let slow = async {
+            trpl::sleep(Duration::from_millis(1_000)).await;
+            Slow
+        };
+
+        let fast = async {
+            trpl::sleep(Duration::from_millis(1)).await;
+            Fast
+        };
+
+        trpl::race(slow, fast).await
+    });
+
+    assert!(matches!(val, Either::Right(Fast)));
+}
+
+mod select_map {
+    use super::*;
+
+    async fn after<T>(ms: u64, value: T) -> T {
+        trpl::sleep(Duration::from_millis(ms)).await;
+        value
+    }
+
+    #[test]
+    fn left_wins() {
+        let result = trpl::block_on(trpl::select_map(
+            after(1, "Ferris"),
+            after(1_000, 7),
+            |name| format!("{name} won"),
+            |n| format!("number {n} won"),
+        ));
+
+        assert_eq!(result, "Ferris won");
+    }
+
+    #[test]
+    fn right_wins() {
+        let result = trpl::block_on(trpl::select_map(
+            after(1_000, "Ferris"),
+            after(1, 7),
+            |name| format!("{name} won"),
+            |n| format!("number {n} won"),
+        ));
+
+        assert_eq!(result, "number 7 won");
+    }
+}
+
+#[test]
+fn race_keep() {
+    let (winner, loser) = trpl::block_on(async {
+        let fast = trpl::pin!(async { "fast" });
+        let slow = trpl::pin!(async {
+            trpl::sleep(Duration::from_millis(10)).await;
+            "slow"
+        });
+
+        let (winner, loser) = trpl::race_keep(fast, slow).await;
+        let loser = match loser {
+            Either::Left(slow) => slow.await,
+            Either::Right(fast) => fast.await,
+        };
+        (winner, loser)
+    });
+
+    assert_eq!(winner, Either::Left("fast"));
+    assert_eq!(loser, "slow");
+}
+
+mod race3 {
+    use super::*;
+
+    use trpl::Either3;
+
+    async fn slow<T>(value: T) -> T {
+        trpl::sleep(Duration::from_millis(1_000)).await;
+        value
+    }
+
+    #[test]
+    fn left_wins() {
+        let val = trpl::block_on(async {
+            trpl::race3(async { 1 }, slow("two"), slow('3')).await
+        });
+
+        assert_eq!(val, Either3::Left(1));
+    }
+
+    #[test]
+    fn middle_wins() {
+        let val = trpl::block_on(async {
+            trpl::race3(slow(1), async { "two" }, slow('3')).await
+        });
+
+        assert_eq!(val, Either3::Middle("two"));
+    }
+
+    #[test]
+    fn right_wins() {
+        let val = trpl::block_on(async {
+            trpl::race3(slow(1), slow("two"), async { '3' }).await
+        });
+
+        assert_eq!(val, Either3::Right('3'));
+    }
+}
+
+#[test]
+fn race_biased_prefers_first_when_both_ready() {
+    trpl::block_on(async {
+        // `select!` is unbiased: it picks a ready branch at random, so in 100
+        // runs it is all but certain to pick the second one at least once.
+        let mut unbiased_picked_second = false;
+        for _ in 0..100 {
+            let result = trpl::race_biased(async { 1 }, async { 2 }).await;
+            assert_eq!(result, Either::Left(1));
+
+            let unbiased = trpl::select! {
+                a = async { 1 } => a,
+                b = async { 2 } => b,
+            };
+            unbiased_picked_second |= unbiased == 2;
+        }
+        assert!(unbiased_picked_second);
+    });
+}
+
+mod race_with_index {
+    use super::*;
+
+    async fn after(ms: u64) -> u64 {
+        trpl::sleep(Duration::from_millis(ms)).await;
+        ms
+    }
+
+    #[test]
+    fn first_wins() {
+        let result =
+            trpl::block_on(trpl::race_with_index(after(1), after(1_000)));
+        assert_eq!(result, (0, 1));
+    }
+
+    #[test]
+    fn second_wins() {
+        let result =
+            trpl::block_on(trpl::race_with_index(after(1_000), after(1)));
+        assert_eq!(result, (1, 1));
+    }
+}
+
+mod race_all {
+    use super::*;
+
+    #[test]
+    fn returns_index_of_first_to_finish() {
+        let result = trpl::block_on(async {
+            let futures = [30, 1, 20]
+                .into_iter()
+                .map(|ms| async move {
+                    trpl::sleep(Duration::from_millis(ms)).await;
+                    format!("slept for {ms}ms")
+                })
+                .collect();
+
+            trpl::race_all(futures).await
+        });
+
+        assert_eq!(result, (1, String::from("slept for 1ms")));
+    }
+
+    #[test]
+    #[should_panic(expected = "called `race_all` with an empty `Vec`")]
+    fn panics_when_empty() {
+        trpl::block_on(async {
+            let futures: Vec<Pin<Box<dyn Future<Output = ()>>>> = vec![];
+            trpl::race_all(futures).await
+        });
+    }
+}
+
+#[test]
+fn first_to_complete() {
+    let result = trpl::block_on(async {
+        let mut futures: Vec<Pin<Box<dyn Future<Output = u64>>>> = vec![];
+        for ms in [30, 1, 20] {
+            futures.push(Box::pin(async move {
+                trpl::sleep(Duration::from_millis(ms)).await;
+                ms
+            }));
+        }
+
+        trpl::first_to_complete(futures).await
+    });
+
+    assert_eq!(result, 1);
+}
+
+#[test]
+fn race_n() {
+    let greeting = String::from("Hello");
+    let result = trpl::block_on(async {
+        let slow = trpl::sleep(Duration::from_millis(30));
+        trpl::race_n! {
+            slow => |()| String::from("sleep"),
+            async {
+                trpl::sleep(Duration::from_millis(1)).await;
+                &greeting
+            } => |s| format!("{s}, World!"),
+            trpl::pending::<u32>() => |n| n.to_string(),
+            async {
+                trpl::sleep(Duration::from_millis(20)).await;
+                true
+            } => |b| b.to_string(),
+        }
+    });
+
+    assert_eq!(result, "Hello, World!");
+}
+
+mod race_ok {
+    use super::*;
+
+    async fn after<T>(ms: u64, value: T) -> T {
+        trpl::sleep(Duration::from_millis(ms)).await;
+        value
+    }
+
+    #[test]
+    fn first_succeeds() {
+        let result: Result<&str, &str> = trpl::block_on(async {
+            trpl::race_ok(after(1, Ok("first")), after(1_000, Ok("second")))
+                .await
+        });
+
+        assert_eq!(result, Ok("first"));
+    }
+
+    #[test]
+    fn second_succeeds_after_first_errors() {
+        let result: Result<&str, &str> = trpl::block_on(async {
+            trpl::race_ok(after(1, Err("first")), after(10, Ok("second"))).await
+        });
+
+        assert_eq!(result, Ok("second"));
+    }
+
+    #[test]
+    fn both_error() {
+        let result: Result<&str, &str> = trpl::block_on(async {
+            trpl::race_ok(after(10, Err("first")), after(1, Err("second")))
+                .await
+        });
+
+        assert_eq!(result, Err("first"));
+    }
+}
+
+mod race_result {
+    use super::*;
+
+    async fn after<T>(ms: u64, value: T) -> T {
+        trpl::sleep(Duration::from_millis(ms)).await;
+        value
+    }
+
+    #[test]
+    fn first_wins_with_ok() {
+        let result: Result<&str, &str> = trpl::block_on(trpl::race_result(
+            after(1, Ok("first")),
+            after(1_000, Ok("second")),
+        ));
+        assert_eq!(result, Ok("first"));
+    }
+
+    #[test]
+    fn first_wins_with_err() {
+        let result: Result<&str, &str> = trpl::block_on(trpl::race_result(
+            after(1, Err("first")),
+            after(1_000, Ok("second")),
+        ));
+        assert_eq!(result, Err("first"));
+    }
+
+    #[test]
+    fn second_wins_with_ok() {
+        let result: Result<&str, &str> = trpl::block_on(trpl::race_result(
+            after(1_000, Ok("first")),
+            after(1, Ok("second")),
+        ));
+        assert_eq!(result, Ok("second"));
+    }
+
+    #[test]
+    fn second_wins_with_err() {
+        let result: Result<&str, &str> = trpl::block_on(trpl::race_result(
+            after(1_000, Ok("first")),
+            after(1, Err("second")),
+        ));
+        assert_eq!(result, Err("second"));
+    }
+}
+
+mod race_timeout {
+    use super::*;
+
+    #[test]
+    fn future_wins() {
+        let result =
+            trpl::block_on(trpl::race_timeout(Duration::from_secs(1), async {
+                "Hello"
+            }));
+        assert_eq!(result, Either::Left("Hello"));
+    }
+
+    #[test]
+    fn timer_wins() {
+        let result = trpl::block_on(trpl::race_timeout(
+            Duration::from_millis(1),
+            async {
+                trpl::sleep(Duration::from_secs(1)).await;
+                "Hello"
+            },
+        ));
+        assert_eq!(result, Either::Right(()));
+    }
+}
+
+mod timeout {
+    use super::*;
+
+    #[test]
+    fn completes_in_time() {
+        let result = trpl::block_on(async {
+            trpl::timeout(Duration::from_secs(1), async { "Hello" }).await
+        });
+
+        assert_eq!(result, Ok("Hello"));
+    }
+
+    #[test]
+    fn times_out() {
+        let max = Duration::from_millis(1);
+        let result = trpl::block_on(async {
+            trpl::timeout(max, trpl::sleep(Duration::from_secs(1))).await
+        });
+
+        assert_eq!(result, Err(max));
+    }
+
+    #[test]
+    fn at_completes_in_time() {
+        let deadline = trpl::Instant::now() + Duration::from_secs(1);
+        let result = trpl::block_on(async {
+            trpl::timeout_at(deadline, async { "Hello" }).await
+        });
+
+        assert_eq!(result, Ok("Hello"));
+    }
+
+    #[test]
+    fn at_times_out() {
+        let deadline = trpl::Instant::now() + Duration::from_millis(1);
+        let result = trpl::block_on(async {
+            trpl::timeout_at(deadline, trpl::sleep(Duration::from_secs(1)))
+                .await
+        });
+
+        assert_eq!(result, Err(deadline));
+    }
+
+    #[test]
+    fn with_remaining_completes_in_time() {
+        let max = Duration::from_secs(10);
+        let result = trpl::block_on(async {
+            trpl::timeout_with_remaining(max, async { "Hello" }).await
+        });
+
+        let (output, remaining) = result.unwrap();
+        assert_eq!(output, "Hello");
+        assert!(remaining > Duration::ZERO);
+        assert!(remaining <= max);
+    }
+
+    #[test]
+    fn with_remaining_times_out() {
+        let max = Duration::from_millis(1);
+        let result = trpl::block_on(async {
+            trpl::timeout_with_remaining(
+                max,
+                trpl::sleep(Duration::from_secs(1)),
+            )
+            .await
+        });
+
+        assert_eq!(result, Err(max));
+    }
+
+    #[test]
+    fn join_completes_in_time() {
+        let result = trpl::block_on(trpl::join_with_timeout(
+            Duration::from_secs(1),
+            async { "Hello" },
+            async {
+                trpl::sleep(Duration::from_millis(1)).await;
+                42
+            },
+        ));
+
+        assert_eq!(result, Ok(("Hello", 42)));
+    }
+
+    #[test]
+    fn join_times_out_when_one_hangs() {
+        let max = Duration::from_millis(10);
+        let result = trpl::block_on(trpl::join_with_timeout(
+            max,
+            async { "Hello" },
+            trpl::pending::<i32>(),
+        ));
+
+        assert_eq!(result, Err(max));
+    }
+
+    #[test]
+    fn with_timeout_completes_in_time() {
+        use trpl::TimeoutExt;
+
+        let result = trpl::block_on(async {
+            async { "Hello" }.with_timeout(Duration::from_secs(1)).await
+        });
+
+        assert_eq!(result, Ok("Hello"));
+    }
+
+    #[test]
+    fn with_timeout_times_out() {
+        use trpl::TimeoutExt;
+
+        let max = Duration::from_millis(1);
+        let result = trpl::block_on(async {
+            trpl::sleep(Duration::from_secs(1)).with_timeout(max).await
+        });
+
+        assert_eq!(result, Err(max));
+    }
+
+    #[test]
+    fn or_default_completes_in_time() {
+        let result = trpl::block_on(async {
+            trpl::timeout_or_default(Duration::from_secs(1), async { 42 }).await
+        });
+
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn or_default_times_out() {
+        let result = trpl::block_on(async {
+            trpl::timeout_or_default(Duration::from_millis(1), async {
+                trpl::sleep(Duration::from_secs(1)).await;
+                42
+            })
+            .await
+        });
+
+        assert_eq!(result, 0);
+    }
+}
+
+mod timeout_join {
+    use super::*;
+
+    #[test]
+    fn completes_in_time() {
+        let result = trpl::block_on(async {
+            let handle = trpl::spawn_task(async { "Hello" });
+            trpl::timeout_join(Duration::from_secs(1), handle).await
+        });
+
+        assert_eq!(result, Ok("Hello"));
+    }
+
+    #[test]
+    fn times_out_and_aborts() {
+        let max = Duration::from_millis(1);
+        let (result, received) = trpl::block_on(async {
+            let (tx, mut rx) = trpl::channel();
+            let handle = trpl::spawn_task(async move {
+                trpl::sleep(Duration::from_secs(1)).await;
+                tx.send("too late").unwrap();
+            });
+            let result = trpl::timeout_join(max, handle).await;
+
+            // If the task was aborted, it dropped `tx` without sending.
+            (result, rx.recv().await)
+        });
+
+        assert_eq!(result, Err(max));
+        assert_eq!(received, None);
+    }
+}
+
+mod spawn_task_with_timeout {
+    use super::*;
+
+    #[test]
+    fn completes_in_time() {
+        let result = trpl::block_on(async {
+            trpl::spawn_task_with_timeout(Duration::from_secs(1), async {
+                "Hello"
+            })
+            .await
+        });
+
+        assert_eq!(result, Ok("Hello"));
+    }
+
+    #[test]
+    fn times_out_and_aborts() {
+        let max = Duration::from_millis(1);
+        let (result, received) = trpl::block_on(async {
+            let (tx, mut rx) = trpl::channel();
+            let result = trpl::spawn_task_with_timeout(max, async move {
+                trpl::sleep(Duration::from_secs(1)).await;
+                tx.send("too late").unwrap();
+            })
+            .await;
+
+            // If the task was aborted, it dropped `tx` without sending.
+            (result, rx.recv().await)
+        });
+
+        assert_eq!(result, Err(max));
+        assert_eq!(received, None);
+    }
+}
+
+mod retry {
+    use super::*;
+
+    #[test]
+    fn succeeds_on_third_attempt() {
+        let mut calls = 0;
+        let result =
+            trpl::block_on(trpl::retry(5, Duration::from_millis(1), || {
+                calls += 1;
+                let attempt = calls;
+                async move {
+                    if attempt < 3 {
+                        Err(format!("attempt {attempt} failed"))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            }));
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn returns_last_error_when_all_attempts_fail() {
+        let mut calls = 0;
+        let result: Result<(), String> =
+            trpl::block_on(trpl::retry(3, Duration::from_millis(1), || {
+                calls += 1;
+                let attempt = calls;
+                async move { Err(format!("attempt {attempt} failed")) }
+            }));
+
+        assert_eq!(result, Err(String::from("attempt 3 failed")));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "called `retry` with zero attempts")]
+    fn panics_with_zero_attempts() {
+        let _: Result<(), ()> =
+            trpl::block_on(trpl::retry(0, Duration::ZERO, || async {
+                Err(())
+            }));
+    }
+}
+
+mod timeout_retry {
+    use super::*;
+
+    #[test]
+    fn succeeds_after_a_timeout_and_an_error() {
+        let mut calls = 0;
+        let result = trpl::block_on(trpl::timeout_retry(
+            5,
+            Duration::from_millis(10),
+            || {
+                calls += 1;
+                let attempt = calls;
+                async move {
+                    match attempt {
+                        1 => {
+                            trpl::sleep(Duration::from_secs(1)).await;
+                            Ok(attempt)
+                        }
+                        2 => Err("attempt 2 failed"),
+                        _ => Ok(attempt),
+                    }
+                }
+            },
+        ));
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn returns_last_error_when_all_attempts_fail() {
+        let mut calls = 0;
+        let result: Result<(), _> = trpl::block_on(trpl::timeout_retry(
+            3,
+            Duration::from_secs(1),
+            || {
+                calls += 1;
+                let attempt = calls;
+                async move { Err(format!("attempt {attempt} failed")) }
+            },
+        ));
+
+        assert_eq!(result, Err(Either::Left(String::from("attempt 3 failed"))));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn returns_timeout_when_all_attempts_time_out() {
+        let max = Duration::from_millis(1);
+        let mut calls = 0;
+        let result: Result<(), Either<(), Duration>> =
+            trpl::block_on(trpl::timeout_retry(3, max, || {
+                calls += 1;
+                async {
+                    trpl::sleep(Duration::from_secs(1)).await;
+                    Ok(())
+                }
+            }));
+
+        assert_eq!(result, Err(Either::Right(max)));
+        assert_eq!(calls, 3);
+    }
+}
+
+#[test]
+fn yield_now_lets_other_tasks_make_progress() {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    // With only one worker thread, each task can only finish once the other
+    // one has started, which only happens if they take turns by yielding.
+    let finished = trpl::block_on_with_threads(1, async {
+        let started = [
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        ];
+
+        let handles: Vec<_> = [(0, 1), (1, 0)]
+            .into_iter()
+            .map(|(me, other)| {
+                let mine = Arc::clone(&started[me]);
+                let theirs = Arc::clone(&started[other]);
+                trpl::spawn_task(async move {
+                    mine.store(true, Ordering::SeqCst);
+                    while !theirs.load(Ordering::SeqCst) {
+                        trpl::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+
+        trpl::timeout(Duration::from_secs(5), trpl::join_all(handles)).await
+    });
+
+    let results = finished.expect("the tasks should both make progress");
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[test]
+fn re_exported_future_ext() {
+    use trpl::FutureExt;
+
+    let result = trpl::block_on(async {
+        async { 1 }
+            .map(|n| n + 1)
+            .then(|n| async move { n * 10 })
+            .await
+    });
+
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn re_exported_fuse() {
+    use std::pin::pin;
+
+    use trpl::{FusedFuture, FutureExt};
+
+    trpl::block_on(async {
+        let mut future = pin!(async { "Hello" }.fuse());
+        assert!(!future.is_terminated());
+
+        assert_eq!(future.as_mut().await, "Hello");
+        assert!(future.is_terminated());
+    });
+}
+
+#[test]
+fn re_exported_pin() {
+    let result = trpl::block_on(async {
+        let mut slow = trpl::pin!(async {
+            trpl::sleep(Duration::from_millis(10)).await;
+            "slow"
+        });
+        let mut fast = trpl::pin!(async { "fast" });
+
+        // `futures::future::select` only takes `Unpin` futures, which pinned
+        // references are, and it does not consume them, so we can keep going.
+        let first = match futures::future::select(&mut slow, &mut fast).await {
+            futures::future::Either::Left((value, _)) => value,
+            futures::future::Either::Right((value, _)) => value,
+        };
+        let second = slow.await;
+
+        (first, second)
+    });
+
+    assert_eq!(result, ("fast", "slow"));
+}
+
+#[test]
+fn read_to_string() {
+    let result = trpl::block_on(async {
+        trpl::read_to_string("tests/integration/to-read.txt")
+            .await
+            .unwrap()
+    });
+
+    assert_eq!(result, String::from("This is some text!\n"));
+}
+
+#[test]
+fn stream_iter() {
+    use trpl::StreamExt;
+
+    let result = trpl::block_on(async {
+        let ns = vec![1, 2, 3];
+        let mut stream = trpl::stream_from_iter(ns);
+        let mut result = vec![];
+        while let Some(n) = stream.next().await {
+            result.push(format!("{n}"));
+        }
+        result
+    });
+
+    assert_eq!(
+        result,
+        vec![String::from("1"), String::from("2"), String::from("3")]
+    )
+}
+
+#[test]
+fn stream_from_fn() {
+    let mut count = 3;
+    let countdown = trpl::stream_from_fn(move || {
+        let next = (count > 0).then(|| {
+            count -= 1;
+            count
+        });
+        async move { next }
+    });
+
+    let result = trpl::block_on(trpl::collect_stream(countdown));
+    assert_eq!(result, vec![2, 1, 0]);
+}
+
+#[test]
+fn repeat_with() {
+    let mut n = 1;
+    let powers_of_two = trpl::repeat_with(move || {
+        let current = n;
+        n *= 2;
+        current
+    });
+
+    let result =
+        trpl::block_on(trpl::collect_stream(trpl::take(powers_of_two, 3)));
+    assert_eq!(result, vec![1, 2, 4]);
+}
+
+#[test]
+fn for_each() {
+    let mut seen = Vec::new();
+    trpl::block_on(trpl::for_each(trpl::stream_from_iter([1, 2, 3]), |n| {
+        seen.push(n)
+    }));
+
+    assert_eq!(seen, vec![1, 2, 3]);
+}
+
+#[test]
+fn fold() {
+    let result = trpl::block_on(trpl::fold(
+        trpl::stream_from_iter([1, 2, 3, 4]),
+        0,
+        |total, n| total + n,
+    ));
+
+    assert_eq!(result, 10);
+}
+
+#[test]
+fn stream_once() {
+    use trpl::StreamExt;
+
+    let result = trpl::block_on(async {
+        let mut stream = std::pin::pin!(trpl::stream_once(async { 42 }));
+        let first = stream.next().await;
+        let second = stream.next().await;
+        (first, second)
+    });
+
+    assert_eq!(result, (Some(42), None));
+}
+
+#[test]
+fn re_exported_stream_iter_and_module() {
+    use trpl::StreamExt;
+
+    let result = trpl::block_on(async {
+        let from_iter: Vec<_> = trpl::iter([1, 2, 3]).collect().await;
+        let from_module: Vec<_> = trpl::stream::iter([4, 5])
+            .chain(trpl::stream::once(async { 6 }))
+            .collect()
+            .await;
+        (from_iter, from_module)
+    });
+
+    assert_eq!(result, (vec![1, 2, 3], vec![4, 5, 6]));
+}
+
+#[test]
+fn receiver_stream() {
+    use trpl::ReceiverStream;
+    use trpl::StreamExt;
+
+    let result: Vec<u32> = trpl::block_on(async {
+        println!("startup");
+        let (tx, rx) = trpl::channel();
+        let rx_stream = ReceiverStream::new(rx);
+        println!("sending 123");
+        tx.send(123).unwrap();
+        drop(tx); // So the receiver channel closes!
+
+        rx_stream.collect().await
+    });
+
+    assert_eq!(result, vec![123]);
+}
+
+#[test]
+fn receiver_stream_fn() {
+    use trpl::StreamExt;
+
+    let result: Vec<u32> = trpl::block_on(async {
+        let (tx, rx) = trpl::channel();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        drop(tx);
+
+        trpl::receiver_stream(rx).collect().await
+    });
+
+    assert_eq!(result, vec![1, 2, 3]);
+}
+
+#[test]
+fn stream_pair() {
+    let result = trpl::block_on(async {
+        let (tx, stream) = trpl::stream_pair();
+        for message in ["a", "b", "c"] {
+            tx.send(message).unwrap();
+        }
+        drop(tx);
+
+        trpl::collect_stream(stream).await
+    });
+
+    assert_eq!(result, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn re_exported_interval_stream_works() {
+    use trpl::{IntervalStream, StreamExt};
+
+    trpl::block_on(async {
+        let mut interval_stream =
+            IntervalStream::new(trpl::interval(Duration::from_millis(1)))
+                .take(1);
+
+        assert!(interval_stream.next().await.is_some());
+        assert!(interval_stream.next().await.is_none());
+    });
+}
+
+#[test]
+fn interval_stream() {
+    use trpl::StreamExt;
+
+    let ticks: Vec<()> = trpl::block_on(async {
+        trpl::interval_stream(Duration::from_millis(1))
+            .take(3)
+            .collect()
+            .await
+    });
+
+    assert_eq!(ticks, vec![(), (), ()]);
+}
+
+#[test]
+fn interval_aligned() {
+    use trpl::{Instant, StreamExt};
+
+    let period = Duration::from_millis(500);
+    let (first_tick, later_ticks) = trpl::block_on(async {
+        let start = Instant::now();
+        let mut ticks = trpl::pin!(trpl::interval_aligned(period));
+
+        ticks.next().await;
+        let first_tick = start.elapsed();
+
+        let mut later_ticks = Vec::new();
+        let mut previous = Instant::now();
+        for _ in 0..2 {
+            ticks.next().await;
+            let now = Instant::now();
+            later_ticks.push(now - previous);
+            previous = now;
+        }
+        (first_tick, later_ticks)
+    });
+
+    // The first tick waits only for the next boundary, which is always less
+    // than a full period away.
+    assert!(first_tick < period, "first tick took {first_tick:?}");
+
+    // After that, ticks keep coming about one period apart. Timers can fire
+    // late on a busy machine, so leave plenty of slack both ways.
+    for gap in later_ticks {
+        assert!(gap > period / 2, "ticks only {gap:?} apart");
+        assert!(gap < period * 2, "ticks {gap:?} apart");
+    }
+}
+
+#[test]
+fn interval_count() {
+    let ticks = trpl::block_on(async {
+        let ticks = trpl::interval_count(Duration::from_millis(1), 4);
+        trpl::collect_stream(ticks).await
+    });
+
+    assert_eq!(ticks, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn counting_interval() {
+    let result = trpl::block_on(async {
+        let ticks = trpl::counting_interval(Duration::from_millis(1));
+        trpl::collect_stream(trpl::take(ticks, 4)).await
+    });
+
+    assert_eq!(result, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn merge() {
+    use trpl::StreamExt;
+
+    let result: Vec<u32> = trpl::block_on(async {
+        let fast = trpl::stream_from_iter([1, 2, 3]);
+        let slow = trpl::stream_once(async {
+            trpl::sleep(Duration::from_millis(10)).await;
+            100
+        });
+
+        trpl::merge(slow, fast).collect().await
+    });
+
+    assert_eq!(result, vec![1, 2, 3, 100]);
+}
+
+#[test]
+fn round_robin() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::round_robin(
+        trpl::stream_from_iter(["a1", "a2", "a3", "a4"]),
+        trpl::stream_from_iter(["b1", "b2"]),
+    )));
+
+    assert_eq!(result, vec!["a1", "b1", "a2", "b2", "a3", "a4"]);
+}
+
+#[test]
+fn zip() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::zip(
+        trpl::stream_from_iter([1, 2, 3]),
+        trpl::stream_from_iter(["a", "b", "c", "d", "e"]),
+    )));
+
+    assert_eq!(result, vec![(1, "a"), (2, "b"), (3, "c")]);
+}
+
+#[test]
+fn throttle() {
+    use trpl::StreamExt;
+
+    let period = Duration::from_millis(10);
+    let start = std::time::Instant::now();
+    let result: Vec<u32> = trpl::block_on(async {
+        trpl::throttle(trpl::stream_from_iter([1, 2, 3]), period)
+            .collect()
+            .await
+    });
+
+    assert_eq!(result, vec![1, 2, 3]);
+    // Only check the lower bound: timers never fire early, but on a busy
+    // machine they can fire arbitrarily late.
+    assert!(start.elapsed() >= period * 2);
+}
+
+#[test]
+fn debounce() {
+    let quiet = Duration::from_millis(20);
+    let result = trpl::block_on(async {
+        let (tx, rx) = trpl::channel();
+        trpl::spawn_task(async move {
+            for n in [1, 2, 3] {
+                tx.send(n).unwrap();
+            }
+            // Leave a gap far longer than `quiet`, so that a late timer still
+            // cannot run the two bursts together.
+            trpl::sleep(quiet * 10).await;
+            for n in [4, 5] {
+                tx.send(n).unwrap();
+            }
+        });
+
+        trpl::collect_stream(trpl::debounce(trpl::receiver_stream(rx), quiet))
+            .await
+    });
+
+    assert_eq!(result, vec![3, 5]);
+}
+
+#[test]
+fn map_stream() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::map_stream(
+        trpl::stream_from_iter([1, 2, 3]),
+        |n| n * n,
+    )));
+
+    assert_eq!(result, vec![1, 4, 9]);
+}
+
+#[test]
+fn filter_map() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::filter_map(
+        trpl::stream_from_iter(1..=6),
+        |n| (n % 2 == 0).then_some(n * 10),
+    )));
+
+    assert_eq!(result, vec![20, 40, 60]);
+}
+
+#[test]
+fn dedup() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::dedup(
+        trpl::stream_from_iter([1, 1, 2, 2, 2, 3, 1]),
+    )));
+
+    assert_eq!(result, vec![1, 2, 3, 1]);
+}
+
+#[test]
+fn scan() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::scan(
+        trpl::stream_from_iter([1, 2, 3, 4]),
+        0,
+        |total, n| {
+            *total += n;
+            Some(*total)
+        },
+    )));
+
+    assert_eq!(result, vec![1, 3, 6, 10]);
+}
+
+#[test]
+fn scan_ends_early_on_none() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::scan(
+        trpl::stream_from_iter([1, 2, 3, 4]),
+        0,
+        |total, n| {
+            *total += n;
+            (*total < 5).then_some(*total)
+        },
+    )));
+
+    assert_eq!(result, vec![1, 3]);
+}
+
+#[test]
+fn flat_map() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::flat_map(
+        trpl::stream_from_iter([1, 2, 3]),
+        |n| trpl::stream_from_iter(std::iter::repeat_n(n, n)),
+    )));
+
+    assert_eq!(result, vec![1, 2, 2, 3, 3, 3]);
+}
+
+#[test]
+fn buffer_unordered() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use trpl::StreamExt;
+
+    let running = AtomicUsize::new(0);
+    let most_running = AtomicUsize::new(0);
+
+    let futures = trpl::stream_from_iter([60, 20, 40, 1]).map(|ms| {
+        let running = &running;
+        let most_running = &most_running;
+        async move {
+            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most_running.fetch_max(now_running, Ordering::SeqCst);
+            trpl::sleep(Duration::from_millis(ms)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            ms
+        }
+    });
+
+    let mut result = trpl::block_on(trpl::collect_stream(
+        trpl::buffer_unordered(futures, 2),
+    ));
+
+    // `buffer_unordered` starts the first two futures in the same poll, before
+    // either can finish, so both are running at once however late the timers
+    // fire, and it never starts a third while two are still going.
+    assert_eq!(most_running.load(Ordering::SeqCst), 2);
+    result.sort();
+    assert_eq!(result, vec![1, 20, 40, 60]);
+}
+
+#[test]
+fn enumerate() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::enumerate(
+        trpl::stream_from_iter(["a", "b", "c"]),
+    )));
+
+    assert_eq!(result, vec![(0, "a"), (1, "b"), (2, "c")]);
+}
+
+#[test]
+fn peekable() {
+    use trpl::StreamExt;
+
+    let (peeked, next, rest) = trpl::block_on(async {
+        let mut stream =
+            trpl::pin!(trpl::peekable(trpl::stream_from_iter([1, 2, 3])));
+
+        let peeked = stream.as_mut().peek().await.copied();
+        let next = stream.next().await;
+        let rest: Vec<_> = stream.collect().await;
+        (peeked, next, rest)
+    });
+
+    assert_eq!(peeked, Some(1));
+    assert_eq!(next, Some(1));
+    assert_eq!(rest, vec![2, 3]);
+}
+
+#[test]
+fn take() {
+    let result = trpl::block_on(async {
+        let ticks = trpl::interval_count(Duration::from_millis(1), usize::MAX);
+        trpl::collect_stream(trpl::take(ticks, 3)).await
     });
+
+    assert_eq!(result, vec![0, 1, 2]);
+}
+
+#[test]
+fn skip() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::skip(
+        trpl::stream_from_iter(["a", "b", "c", "d"]),
+        2,
+    )));
+
+    assert_eq!(result, vec!["c", "d"]);
+}
+
+#[test]
+fn cycle() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::take(
+        trpl::cycle(futures::stream::iter(["tick", "tock"])),
+        5,
+    )));
+
+    assert_eq!(result, vec!["tick", "tock", "tick", "tock", "tick"]);
+}
+
+#[test]
+fn cycle_of_empty_stream_ends() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::take(
+        trpl::cycle(futures::stream::iter(Vec::<i32>::new())),
+        5,
+    )));
+
+    assert_eq!(result, Vec::<i32>::new());
+}
+
+#[test]
+fn chunks() {
+    let result = trpl::block_on(trpl::collect_stream(trpl::chunks(
+        trpl::stream_from_iter(1..=7),
+        3,
+    )));
+
+    assert_eq!(result, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+}
+
+#[test]
+fn timeout_stream() {
+    use trpl::StreamExt;
+
+    let budget = Duration::from_millis(10);
+    let result: Vec<Result<u32, Duration>> = trpl::block_on(async {
+        let (tx, rx) = trpl::channel();
+        trpl::spawn_task(async move {
+            tx.send(1).unwrap();
+            trpl::sleep(budget * 5).await;
+            tx.send(2).unwrap();
+            tx.send(3).unwrap();
+        });
+
+        trpl::timeout_stream(trpl::receiver_stream(rx), budget)
+            .collect()
+            .await
+    });
+
+    assert_eq!(result, vec![Ok(1), Err(budget), Ok(2), Ok(3)]);
+}
+
+#[test]
+fn collect_stream() {
+    let result =
+        trpl::block_on(trpl::collect_stream(trpl::stream_from_iter([
+            "a", "b", "c",
+        ])));
+
+    assert_eq!(result, vec!["a", "b", "c"]);
 }
 
 #[test]